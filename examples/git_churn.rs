@@ -1,5 +1,5 @@
 use clap::Parser;
-use code_map::git_churn::print_git_churn;
+use code_map::git_churn::{print_git_churn, ChurnOptions};
 use code_map::AnyError;
 use std::path::PathBuf;
 
@@ -13,5 +13,9 @@ pub struct Cli {
 }
 
 fn main() -> Result<(), AnyError> {
-    print_git_churn(Cli::parse().input_folder, None)
+    let options = ChurnOptions {
+        use_cache: true,
+        ..Default::default()
+    };
+    print_git_churn(Cli::parse().input_folder, &options)
 }
@@ -0,0 +1,203 @@
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use macroquad::prelude::Rect;
+
+use crate::tree::Tree;
+
+pub mod binary;
+pub mod golden;
+pub mod linear;
+pub mod ordered;
+pub mod squarified;
+pub mod stable;
+
+/// A way of placing the nodes of a tree inside a rectangle. Implement it to plug your own layout
+/// into `ui::Ui`.
+pub trait Arrangement {
+    /// Sets the rect of the node and of all its descendants, inside `rect`.
+    fn arrange(&self, tree: &mut Tree, rect: Rect);
+
+    /// If true, the rects of the previous map are copied to the new tree before arranging it
+    /// after a refresh (see `Tree::copy_rects_from`), so that they can be used as hints.
+    fn uses_previous_rects(&self) -> bool {
+        false
+    }
+}
+
+/// Adds some space around the children of each node, for arrangements that don't support
+/// padding themselves.
+pub struct Padded<A> {
+    pub inner: A,
+    pub padding: f32,
+}
+
+impl<A: Arrangement> Arrangement for Padded<A> {
+    fn arrange(&self, tree: &mut Tree, rect: Rect) {
+        self.inner.arrange(tree, rect);
+        if self.padding > 0.0 {
+            let padding = self.padding;
+            remap_children(tree, &|_, rect| shrink(rect, padding));
+        }
+    }
+
+    fn uses_previous_rects(&self) -> bool {
+        self.inner.uses_previous_rects()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum Arrangements {
+    Binary,
+    Golden,
+    Linear,
+    Ordered,
+    Squarified,
+    Stable,
+}
+
+const ARRANGEMENTS: [Arrangements; 6] = [
+    Arrangements::Binary,
+    Arrangements::Golden,
+    Arrangements::Linear,
+    Arrangements::Ordered,
+    Arrangements::Squarified,
+    Arrangements::Stable,
+];
+
+impl ValueEnum for Arrangements {
+    fn value_variants<'a>() -> &'a [Self] {
+        &ARRANGEMENTS
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            Arrangements::Binary => Some(PossibleValue::new("binary")),
+            Arrangements::Golden => Some(PossibleValue::new("golden")),
+            Arrangements::Linear => Some(PossibleValue::new("linear")),
+            Arrangements::Ordered => Some(PossibleValue::new("ordered")),
+            Arrangements::Squarified => Some(PossibleValue::new("squarified")),
+            Arrangements::Stable => Some(PossibleValue::new("stable")),
+        }
+    }
+}
+
+impl Arrangements {
+    /// `padding` is the space in pixels between a node and its children.
+    pub fn new_arrangement(self, padding: f32) -> Box<dyn Arrangement> {
+        match self {
+            Arrangements::Binary => padded(binary::Binary, padding),
+            Arrangements::Golden => padded(golden::Golden, padding),
+            Arrangements::Linear => Box::new(linear::Linear { padding }),
+            Arrangements::Ordered => padded(ordered::Ordered, padding),
+            Arrangements::Squarified => padded(squarified::Squarified, padding),
+            Arrangements::Stable => padded(stable::Stable, padding),
+        }
+    }
+}
+
+fn padded<A: Arrangement + 'static>(inner: A, padding: f32) -> Box<dyn Arrangement> {
+    if padding > 0.0 {
+        Box::new(Padded { inner, padding })
+    } else {
+        Box::new(inner)
+    }
+}
+
+/// Moves the children of each node (recursively) into the rect returned by `inner_rect`, scaling
+/// their positions so that they keep their proportions. For example, to leave some padding inside
+/// each node, or some space for a header. Nodes that don't fit lose their rects.
+pub fn remap_children(tree: &mut Tree, inner_rect: &dyn Fn(&Tree, Rect) -> Rect) {
+    if let Some(rect) = tree.rect {
+        remap_node(tree, rect, rect, inner_rect);
+    }
+}
+
+fn remap_node(
+    node: &mut Tree,
+    original: Rect,
+    remapped: Rect,
+    inner_rect: &dyn Fn(&Tree, Rect) -> Rect,
+) {
+    node.rect = Some(remapped);
+    if node.children.is_empty() {
+        return;
+    }
+    let inner = inner_rect(node, remapped);
+    let fits = inner.w > 0.0 && inner.h > 0.0 && original.w > 0.0 && original.h > 0.0;
+    for child in &mut node.children {
+        match child.rect {
+            Some(child_original) if fits => {
+                let child_remapped = Rect::new(
+                    inner.x + (child_original.x - original.x) * inner.w / original.w,
+                    inner.y + (child_original.y - original.y) * inner.h / original.h,
+                    child_original.w * inner.w / original.w,
+                    child_original.h * inner.h / original.h,
+                );
+                remap_node(child, child_original, child_remapped, inner_rect);
+            }
+            _ => clear_rects(child),
+        }
+    }
+}
+
+fn clear_rects(node: &mut Tree) {
+    node.rect = None;
+    for child in &mut node.children {
+        clear_rects(child);
+    }
+}
+
+fn shrink(rect: Rect, padding: f32) -> Rect {
+    Rect::new(
+        rect.x + padding,
+        rect.y + padding,
+        rect.w - 2.0 * padding,
+        rect.h - 2.0 * padding,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arrangements::linear::tests::assert_float_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_padding() {
+        #[rustfmt::skip]
+        let mut tree = Tree::new_from_children("root".to_string(), vec![
+            Tree::new_from_children("folder".to_string(), vec![
+                Tree::new_from_size("a".to_string(), 1),
+                Tree::new_from_size("b".to_string(), 1),
+            ]),
+            Tree::new_from_size("c".to_string(), 2),
+        ]);
+        let arrangement = Arrangements::Binary.new_arrangement(1.0);
+
+        arrangement.arrange(&mut tree, Rect::new(0.0, 0.0, 20.0, 10.0));
+
+        let folder = tree.get_nested_by_name("folder")[1].rect.unwrap();
+        assert_eq!(folder, Rect::new(1.0, 1.0, 9.0, 8.0));
+        let a = tree.get_nested_by_name("a")[2].rect.unwrap();
+        assert_float_eq(a.x, 2.0);
+        assert_float_eq(a.y, 2.0);
+        assert_float_eq(a.w, 3.5);
+        assert_float_eq(a.h, 6.0);
+    }
+
+    #[test]
+    fn test_nodes_that_dont_fit_lose_their_rects() {
+        #[rustfmt::skip]
+        let mut tree = Tree::new_from_children("root".to_string(), vec![
+            Tree::new_from_children("folder".to_string(), vec![
+                Tree::new_from_size("a".to_string(), 1),
+            ]),
+        ]);
+        let arrangement = Arrangements::Squarified.new_arrangement(3.0);
+
+        arrangement.arrange(&mut tree, Rect::new(0.0, 0.0, 10.0, 10.0));
+
+        assert!(tree.get_nested_by_name("folder")[1].rect.is_some());
+        assert!(tree.get_nested_by_name("a")[2].rect.is_none());
+    }
+}
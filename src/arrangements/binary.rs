@@ -1,7 +1,16 @@
 use macroquad::prelude::Rect;
 
+use crate::arrangements::Arrangement;
 use crate::tree::Tree;
 
+pub struct Binary;
+
+impl Arrangement for Binary {
+    fn arrange(&self, tree: &mut Tree, rect: Rect) {
+        arrange(tree, rect);
+    }
+}
+
 pub fn arrange(node: &mut Tree, rect: Rect) {
     node.rect = Some(rect);
     let rect = node.rect.unwrap();
@@ -65,14 +74,14 @@ fn get_half_size(nodes: &mut [Tree]) -> Result<(usize, f32), String> {
     }
 }
 
-fn divide_rectangle_horizontally(rect: Rect, coef: f32) -> (Rect, Rect) {
+pub(crate) fn divide_rectangle_horizontally(rect: Rect, coef: f32) -> (Rect, Rect) {
     let width_1 = rect.w * coef;
     let rect_1 = Rect::new(rect.x, rect.y, width_1, rect.h);
     let rect_2 = Rect::new(rect.x + width_1, rect.y, rect.w - width_1, rect.h);
     (rect_1, rect_2)
 }
 
-fn divide_rectangle_vertically(rect: Rect, coef: f32) -> (Rect, Rect) {
+pub(crate) fn divide_rectangle_vertically(rect: Rect, coef: f32) -> (Rect, Rect) {
     let height_1 = rect.h * coef;
     let rect_1 = Rect::new(rect.x, rect.y, rect.w, height_1);
     let rect_2 = Rect::new(rect.x, rect.y + height_1, rect.w, rect.h - height_1);
@@ -101,7 +110,7 @@ fn average_squareness(rectangles: &[Rect]) -> f32 {
 #[cfg(test)]
 mod tests {
     use crate::arrangements::linear::tests::assert_float_eq;
-    use crate::arrangements::{golden, linear};
+    use crate::arrangements::{golden, linear, squarified};
 
     use super::*;
 
@@ -199,17 +208,38 @@ mod tests {
         );
         let area_golden = area(&tree.children);
 
+        squarified::arrange(&mut tree, Rect::new(0.0, 0.0, 1.0, 1.0));
+        let squareness_squarified = average_squareness(
+            &tree
+                .children
+                .iter()
+                .map(|child| child.rect.unwrap())
+                .collect::<Vec<_>>(),
+        );
+        let area_squarified = area(&tree.children);
+
         assert!(
             squareness_binary > squareness_linear,
             "{} < {}",
             squareness_binary,
             squareness_linear
         );
+        assert!(
+            squareness_squarified > squareness_binary,
+            "{} < {}",
+            squareness_squarified,
+            squareness_binary
+        );
         println!("squareness of binary::arrange: {}", squareness_binary);
         println!("squareness of linear::arrange: {}", squareness_linear);
         println!("squareness of golden::arrange: {}", squareness_golden);
+        println!(
+            "squareness of squarified::arrange: {}",
+            squareness_squarified
+        );
         assert_float_eq(area_binary, area_linear);
         assert_float_eq(area_binary, area_golden);
+        assert_float_eq(area_binary, area_squarified);
     }
 
     #[test]
@@ -1,7 +1,16 @@
 use macroquad::prelude::Rect;
 
+use crate::arrangements::Arrangement;
 use crate::tree::Tree;
 
+pub struct Golden;
+
+impl Arrangement for Golden {
+    fn arrange(&self, tree: &mut Tree, rect: Rect) {
+        arrange(tree, rect);
+    }
+}
+
 pub fn arrange(node: &mut Tree, rect: Rect) {
     node.rect = Some(rect);
     let rect = node.rect.unwrap();
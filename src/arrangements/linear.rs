@@ -1,7 +1,18 @@
 use macroquad::prelude::Rect;
 
+use crate::arrangements::Arrangement;
 use crate::tree::Tree;
 
+pub struct Linear {
+    pub padding: f32,
+}
+
+impl Arrangement for Linear {
+    fn arrange(&self, tree: &mut Tree, rect: Rect) {
+        arrange(tree, rect, self.padding);
+    }
+}
+
 pub fn arrange(node: &mut Tree, rect: Rect, pad: f32) {
     node.rect = Some(rect);
     let mut rect = node.rect.unwrap();
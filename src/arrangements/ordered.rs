@@ -0,0 +1,108 @@
+use macroquad::prelude::Rect;
+
+use crate::arrangements::binary::{divide_rectangle_horizontally, divide_rectangle_vertically};
+use crate::arrangements::Arrangement;
+use crate::tree::Tree;
+
+pub struct Ordered;
+
+impl Arrangement for Ordered {
+    fn arrange(&self, tree: &mut Tree, rect: Rect) {
+        arrange(tree, rect);
+    }
+}
+
+/// Like the binary arrangement, but the children are kept sorted by name instead of by size, so
+/// that a file stays in roughly the same place when sizes change, or when switching metrics.
+/// The children are split in 2 groups of consecutive names with similar total size, and the
+/// first group goes to the left or top half of the rectangle.
+pub fn arrange(node: &mut Tree, rect: Rect) {
+    node.rect = Some(rect);
+    if rect.w * rect.h == 0.0 {
+        return;
+    }
+    node.children.sort_by(|a, b| a.name.cmp(&b.name));
+    arrange_nodes(&mut node.children, rect);
+}
+
+fn arrange_nodes(nodes: &mut [Tree], rect: Rect) {
+    if nodes.is_empty() {
+    } else if nodes.len() == 1 {
+        arrange(&mut nodes[0], rect);
+    } else {
+        let (split_index, first_coef) = get_balanced_split(nodes);
+        let (rect_1, rect_2) = if rect.w >= rect.h {
+            divide_rectangle_horizontally(rect, first_coef)
+        } else {
+            divide_rectangle_vertically(rect, first_coef)
+        };
+        let (first, second) = nodes.split_at_mut(split_index);
+        arrange_nodes(first, rect_1);
+        arrange_nodes(second, rect_2);
+    }
+}
+
+/// Returns the index that splits the nodes (without reordering them) in 2 non-empty groups whose
+/// sizes are as close as possible, and the proportion of the size that goes to the first group.
+pub(crate) fn get_balanced_split(nodes: &[Tree]) -> (usize, f32) {
+    let total = nodes.iter().map(|node| node.size()).sum::<i64>();
+    if total == 0 {
+        let half = nodes.len() / 2;
+        return (half, half as f32 / nodes.len() as f32);
+    }
+    let mut best = (1, i64::MAX, 0);
+    let mut accumulated = 0;
+    for (i, node) in nodes[..nodes.len() - 1].iter().enumerate() {
+        accumulated += node.size();
+        let imbalance = (2 * accumulated - total).abs();
+        if imbalance < best.1 {
+            best = (i + 1, imbalance, accumulated);
+        }
+    }
+    let (index, _, first_size) = best;
+    (index, (first_size as f64 / total as f64) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arrangements::linear::tests::assert_float_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_keeps_name_order() {
+        #[rustfmt::skip]
+        let mut tree = Tree::new_from_children("parent".to_string(), vec![
+            Tree::new_from_size("c".to_string(), 1),
+            Tree::new_from_size("a".to_string(), 1),
+            Tree::new_from_size("b".to_string(), 10),
+        ]);
+
+        arrange(&mut tree, Rect::new(0.0, 0.0, 12.0, 1.0));
+
+        let names = tree
+            .children
+            .iter()
+            .map(|child| child.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        let a = tree.children[0].rect.unwrap();
+        let b = tree.children[1].rect.unwrap();
+        let c = tree.children[2].rect.unwrap();
+        assert!(a.x < b.x && b.x < c.x, "{:?}, {:?}, {:?}", a, b, c);
+        assert_float_eq(a.w, 1.0);
+        assert_float_eq(b.w, 10.0);
+        assert_float_eq(c.w, 1.0);
+    }
+
+    #[test]
+    fn test_balanced_split() {
+        let nodes = [1, 1, 2, 4]
+            .into_iter()
+            .map(|size| Tree::new_from_size("".to_string(), size))
+            .collect::<Vec<_>>();
+        let (index, coef) = get_balanced_split(&nodes);
+        assert_eq!(index, 3);
+        assert_float_eq(coef, 0.5);
+    }
+}
@@ -0,0 +1,138 @@
+use std::cmp::Reverse;
+
+use macroquad::prelude::Rect;
+
+use crate::arrangements::Arrangement;
+use crate::tree::Tree;
+
+pub struct Squarified;
+
+impl Arrangement for Squarified {
+    fn arrange(&self, tree: &mut Tree, rect: Rect) {
+        arrange(tree, rect);
+    }
+}
+
+/// The squarified treemap from "Squarified Treemaps" by Bruls, Huizing and van Wijk. The children
+/// are laid out in rows along the shorter side of the rectangle, and a child is added to the
+/// current row only if that doesn't make the worst aspect ratio of the row worse.
+pub fn arrange(node: &mut Tree, rect: Rect) {
+    node.rect = Some(rect);
+    if rect.w * rect.h == 0.0 {
+        return;
+    }
+    node.children.sort_by_key(|child| Reverse(child.size()));
+    let areas = scaled_areas(&node.children, rect);
+    let mut remaining = rect;
+    let mut start = 0;
+    while start < node.children.len() {
+        let side = remaining.w.min(remaining.h);
+        let mut end = start + 1;
+        while end < areas.len()
+            && worst_ratio(&areas[start..=end], side) <= worst_ratio(&areas[start..end], side)
+        {
+            end += 1;
+        }
+        remaining = arrange_row(
+            &mut node.children[start..end],
+            &areas[start..end],
+            remaining,
+        );
+        start = end;
+    }
+}
+
+/// The area that each node should have, so that all of them fill the rectangle.
+fn scaled_areas(nodes: &[Tree], rect: Rect) -> Vec<f32> {
+    let total_area = rect.w * rect.h;
+    let total_size = nodes.iter().map(|node| node.size()).sum::<i64>();
+    if total_size == 0 {
+        vec![total_area / nodes.len() as f32; nodes.len()]
+    } else {
+        nodes
+            .iter()
+            .map(|node| (node.size() as f64 / total_size as f64) as f32 * total_area)
+            .collect()
+    }
+}
+
+/// The aspect ratio (always >= 1) of the most elongated rectangle if the areas are laid out in a
+/// row along a side of this length.
+fn worst_ratio(areas: &[f32], side: f32) -> f32 {
+    let sum = areas.iter().sum::<f32>();
+    let max = areas.iter().copied().fold(0.0, f32::max);
+    let min = areas.iter().copied().fold(f32::INFINITY, f32::min);
+    if min <= 0.0 {
+        f32::INFINITY
+    } else {
+        let side_squared = side * side;
+        let sum_squared = sum * sum;
+        (side_squared * max / sum_squared).max(sum_squared / (side_squared * min))
+    }
+}
+
+/// Arranges the nodes in a row along the shorter side of the rectangle, and returns the rectangle
+/// that is left for the next rows.
+fn arrange_row(nodes: &mut [Tree], areas: &[f32], rect: Rect) -> Rect {
+    let row_area = areas.iter().sum::<f32>();
+    let count = nodes.len() as f32;
+    if rect.w >= rect.h {
+        let width = (row_area / rect.h).min(rect.w);
+        let mut y = rect.y;
+        for (node, area) in nodes.iter_mut().zip(areas) {
+            let height = if row_area == 0.0 {
+                rect.h / count
+            } else {
+                area / width
+            };
+            arrange(node, Rect::new(rect.x, y, width, height));
+            y += height;
+        }
+        Rect::new(rect.x + width, rect.y, rect.w - width, rect.h)
+    } else {
+        let height = (row_area / rect.w).min(rect.h);
+        let mut x = rect.x;
+        for (node, area) in nodes.iter_mut().zip(areas) {
+            let width = if row_area == 0.0 {
+                rect.w / count
+            } else {
+                area / height
+            };
+            arrange(node, Rect::new(x, rect.y, width, height));
+            x += width;
+        }
+        Rect::new(rect.x, rect.y + height, rect.w, rect.h - height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arrangements::linear::tests::assert_float_eq;
+
+    use super::*;
+
+    /// The example from the paper: a 6x4 rectangle where the first row has the two biggest nodes.
+    #[test]
+    fn test_paper_example() {
+        let mut children = Vec::new();
+        for (i, size) in [6, 6, 4, 3, 2, 2, 1].into_iter().enumerate() {
+            children.push(Tree::new_from_size(format!("child_{}", i), size));
+        }
+        let mut tree = Tree::new_from_children("parent".to_string(), children);
+
+        arrange(&mut tree, Rect::new(0.0, 0.0, 6.0, 4.0));
+
+        let first = tree.children[0].rect.unwrap();
+        let second = tree.children[1].rect.unwrap();
+        assert_float_eq(first.w, 3.0);
+        assert_float_eq(first.h, 2.0);
+        assert_float_eq(second.y, 2.0);
+        assert_float_eq(second.w, 3.0);
+        let area = tree
+            .children
+            .iter()
+            .map(|child| child.rect.unwrap().w * child.rect.unwrap().h)
+            .sum::<f32>();
+        assert_float_eq(area, 24.0);
+    }
+}
@@ -0,0 +1,148 @@
+use std::cmp::Ordering;
+
+use macroquad::prelude::Rect;
+
+use crate::arrangements::binary::{divide_rectangle_horizontally, divide_rectangle_vertically};
+use crate::arrangements::ordered::get_balanced_split;
+use crate::arrangements::Arrangement;
+use crate::tree::Tree;
+
+pub struct Stable;
+
+impl Arrangement for Stable {
+    fn arrange(&self, tree: &mut Tree, rect: Rect) {
+        arrange(tree, rect);
+    }
+
+    fn uses_previous_rects(&self) -> bool {
+        true
+    }
+}
+
+/// Uses the rects that the nodes already have (e.g. copied from a previous arrangement with
+/// `Tree::copy_rects_from`) as hints, so that small changes in the sizes produce small changes in
+/// the picture. Like the ordered arrangement, the children are split recursively in 2 groups of
+/// similar size, but each time they are sorted by where they were along the side being split.
+/// Nodes without a hint go after the ones with a hint, sorted by name.
+pub fn arrange(node: &mut Tree, rect: Rect) {
+    node.rect = Some(rect);
+    if rect.w * rect.h == 0.0 {
+        return;
+    }
+    node.children.sort_by(|a, b| a.name.cmp(&b.name));
+    arrange_nodes(&mut node.children, rect);
+}
+
+fn arrange_nodes(nodes: &mut [Tree], rect: Rect) {
+    if nodes.is_empty() {
+    } else if nodes.len() == 1 {
+        arrange(&mut nodes[0], rect);
+    } else {
+        let split_width = rect.w >= rect.h;
+        // a stable sort, so that nodes without hints keep the name order
+        nodes.sort_by(|a, b| compare_hints(a.rect, b.rect, split_width));
+        let (split_index, first_coef) = get_balanced_split(nodes);
+        let (rect_1, rect_2) = if split_width {
+            divide_rectangle_horizontally(rect, first_coef)
+        } else {
+            divide_rectangle_vertically(rect, first_coef)
+        };
+        let (first, second) = nodes.split_at_mut(split_index);
+        arrange_nodes(first, rect_1);
+        arrange_nodes(second, rect_2);
+    }
+}
+
+fn compare_hints(a: Option<Rect>, b: Option<Rect>, horizontally: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => center(a, horizontally).total_cmp(&center(b, horizontally)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn center(rect: Rect, horizontally: bool) -> f32 {
+    if horizontally {
+        rect.x + rect.w * 0.5
+    } else {
+        rect.y + rect.h * 0.5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arrangements::golden;
+
+    use super::*;
+
+    #[test]
+    fn test_follows_hints() {
+        #[rustfmt::skip]
+        let mut tree = Tree::new_from_children("parent".to_string(), vec![
+            Tree::new_from_size("a".to_string(), 1),
+            Tree::new_from_size("b".to_string(), 1),
+            Tree::new_from_size("c".to_string(), 1),
+        ]);
+        tree.children[0].rect = Some(Rect::new(8.0, 0.0, 1.0, 1.0));
+        tree.children[1].rect = Some(Rect::new(0.0, 0.0, 1.0, 1.0));
+
+        arrange(&mut tree, Rect::new(0.0, 0.0, 3.0, 1.0));
+
+        let names = tree
+            .children
+            .iter()
+            .map(|child| child.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_small_changes_move_less_than_sorting_by_size() {
+        let sizes = [10, 8, 7, 5, 3, 2, 2, 1];
+        let tree = |last_grows: i64| {
+            let children = sizes
+                .iter()
+                .enumerate()
+                .map(|(i, size)| {
+                    let grow = if i == sizes.len() - 1 { last_grows } else { 0 };
+                    Tree::new_from_size(format!("child_{}", i), size + grow)
+                })
+                .collect();
+            Tree::new_from_children("parent".to_string(), children)
+        };
+        let available = Rect::new(0.0, 0.0, 16.0, 9.0);
+
+        let mut before = tree(0);
+        arrange(&mut before, available);
+        let mut after = tree(9);
+        after.copy_rects_from(&before);
+        arrange(&mut after, available);
+        let moved_stable = total_displacement(&before, &after);
+
+        let mut before = tree(0);
+        golden::arrange(&mut before, available);
+        let mut after = tree(9);
+        golden::arrange(&mut after, available);
+        let moved_golden = total_displacement(&before, &after);
+
+        assert!(
+            moved_stable < moved_golden,
+            "{} >= {}",
+            moved_stable,
+            moved_golden
+        );
+    }
+
+    fn total_displacement(before: &Tree, after: &Tree) -> f32 {
+        before
+            .children
+            .iter()
+            .map(|child| {
+                let moved = after.get_nested_by_name(&child.name)[1];
+                let before_center = child.rect.unwrap().center();
+                before_center.distance(moved.rect.unwrap().center())
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use macroquad::prelude::info;
+
+use crate::heatmap::{Heatmap, Mark, Scale};
+use crate::metrics::churn_per_file::nodes_flat_list_to_tree;
+use crate::tree::{relative_path, Tree};
+use crate::AnyError;
+
+/// The changes of a metric between two trees, e.g. the lines of two releases.
+pub struct Diff {
+    /// The size of each file is how much it changed, either growing or shrinking. Files that
+    /// didn't change are left out, so the root has no children if nothing changed.
+    pub tree: Tree,
+    pub units: String,
+    /// The signed change of each file, and which files were added or removed.
+    pub heatmap: Heatmap,
+}
+
+impl Diff {
+    /// The leaves of both trees are matched by their path relative to the root of each tree, and
+    /// the resulting tree has the same root name as `after`.
+    pub fn new(before: &Tree, after: &Tree, units: &str) -> Result<Self, AnyError> {
+        let before_sizes = before.leaf_sizes_by_path();
+        let after_sizes = after.leaf_sizes_by_path();
+        let paths = before_sizes
+            .keys()
+            .chain(after_sizes.keys())
+            .collect::<BTreeSet<_>>();
+
+        let mut nodes = Vec::new();
+        let mut deltas = HashMap::new();
+        let mut marks = HashMap::new();
+        for path in paths {
+            let (before_size, after_size) = (before_sizes.get(path), after_sizes.get(path));
+            let delta = after_size.unwrap_or(&0) - before_size.unwrap_or(&0);
+            if delta == 0 {
+                continue;
+            }
+            nodes.push(Tree::new_from_size(path.clone(), delta.abs()));
+            deltas.insert(path.clone(), delta);
+            match (before_size, after_size) {
+                (None, Some(_)) => marks.insert(path.clone(), Mark::Added),
+                (Some(_), None) => marks.insert(path.clone(), Mark::Removed),
+                _ => None,
+            };
+        }
+        let tree = if nodes.is_empty() {
+            info!("there are no differences between the trees");
+            Tree::new_from_children(after.name.clone(), Vec::new())
+        } else {
+            nodes_flat_list_to_tree(nodes, PathBuf::from(&after.name))?
+        };
+
+        // the tree adds the root folder to the names, and the heatmap needs the final names
+        let root = tree.name.clone();
+        let mut values = HashMap::new();
+        let mut final_marks = HashMap::new();
+        collect_final_names(&tree, &root, &deltas, &marks, &mut values, &mut final_marks);
+        let heatmap = Heatmap::from_values(values, final_marks, units, Scale::Diverging);
+        Ok(Self {
+            tree,
+            units: format!("{} (absolute change)", units),
+            heatmap,
+        })
+    }
+}
+
+fn collect_final_names(
+    node: &Tree,
+    root: &str,
+    deltas: &HashMap<String, i64>,
+    marks: &HashMap<String, Mark>,
+    values: &mut HashMap<String, i64>,
+    final_marks: &mut HashMap<String, Mark>,
+) {
+    if node.children.is_empty() {
+        let path = relative_path(root, &node.name);
+        if let Some(delta) = deltas.get(&path) {
+            values.insert(node.name.clone(), *delta);
+        }
+        if let Some(mark) = marks.get(&path) {
+            final_marks.insert(node.name.clone(), *mark);
+        }
+    } else {
+        for child in &node.children {
+            collect_final_names(child, root, deltas, marks, values, final_marks);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        #[rustfmt::skip]
+        let before = Tree::new_from_children("./".into(), vec![
+            Tree::new_from_children("./src".into(), vec![
+                Tree::new_from_size("./src/main.rs".into(), 100),
+                Tree::new_from_size("./src/same.rs".into(), 30),
+                Tree::new_from_size("./src/removed.rs".into(), 20),
+            ]),
+        ]);
+        #[rustfmt::skip]
+        let after = Tree::new_from_children(".".into(), vec![
+            Tree::new_from_children("./src".into(), vec![
+                Tree::new_from_size("./src/main.rs".into(), 90),
+                Tree::new_from_size("./src/same.rs".into(), 30),
+                Tree::new_from_size("./src/added.rs".into(), 50),
+            ]),
+        ]);
+
+        let diff = Diff::new(&before, &after, "lines").unwrap();
+
+        #[rustfmt::skip]
+        let expected = Tree::new_from_computed_size(".".into(), 80, vec![
+            Tree::new_from_computed_size("./src".into(), 80, vec![
+                Tree::new_from_size("./src/added.rs".into(), 50),
+                Tree::new_from_size("./src/main.rs".into(), 10),
+                Tree::new_from_size("./src/removed.rs".into(), 20),
+            ]),
+        ]);
+        assert!(diff.tree.recursive_equals(&expected), "{:#?}", diff.tree);
+        assert_eq!(diff.heatmap.value("./src/main.rs"), Some(-10));
+        assert_eq!(diff.heatmap.value("./src/added.rs"), Some(50));
+        assert_eq!(diff.heatmap.mark("./src/added.rs"), Some(Mark::Added));
+        assert_eq!(diff.heatmap.mark("./src/removed.rs"), Some(Mark::Removed));
+        assert_eq!(diff.heatmap.mark("./src/main.rs"), None);
+    }
+
+    #[test]
+    fn test_no_differences() {
+        #[rustfmt::skip]
+        let tree = Tree::new_from_children(".".into(), vec![
+            Tree::new_from_size("./main.rs".into(), 1),
+        ]);
+
+        let diff = Diff::new(&tree, &tree, "lines").unwrap();
+
+        let expected = Tree::new_from_computed_size(".".into(), 0, vec![]);
+        assert!(diff.tree.recursive_equals(&expected), "{:#?}", diff.tree);
+        assert_eq!(diff.heatmap.value("./main.rs"), None);
+    }
+}
@@ -0,0 +1,50 @@
+//! Ways of saving the map to files that can be viewed without this program.
+
+use macroquad::color::Color;
+
+pub mod html;
+pub mod svg;
+
+/// Escapes the characters that have a special meaning in XML text and attributes.
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats a color as `#rrggbb`, ignoring the alpha.
+pub fn to_hex(color: Color) -> String {
+    let to_byte = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        to_byte(color.r),
+        to_byte(color.g),
+        to_byte(color.b)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a<b> & \"c\""), "a&lt;b&gt; &amp; &quot;c&quot;");
+        assert_eq!(escape_xml("./src/main.rs"), "./src/main.rs");
+    }
+
+    #[test]
+    fn test_to_hex() {
+        assert_eq!(to_hex(Color::new(0.0, 0.0, 0.0, 1.0)), "#000000");
+        assert_eq!(to_hex(Color::new(1.0, 0.5, 0.0, 1.0)), "#ff8000");
+    }
+}
@@ -0,0 +1,154 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::export::escape_xml;
+use crate::heatmap::{Heatmap, Mark};
+use crate::tree::Tree;
+use crate::ui::map_and_path::COLORS_HEX;
+use crate::AnyError;
+
+/// The page that renders the map, with placeholders for the title and the data.
+const TEMPLATE: &str = include_str!("report.html");
+const TITLE_PLACEHOLDER: &str = "__CODE_MAP_TITLE__";
+const DATA_PLACEHOLDER: &str = "__CODE_MAP_DATA__";
+
+/// Everything the page needs, embedded as JSON.
+#[derive(Serialize)]
+struct Report<'a> {
+    units: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color_units: Option<&'a str>,
+    width: f32,
+    height: f32,
+    /// the colors of each level of the selected path, as in the window
+    path_colors: Vec<String>,
+    tree: ReportNode<'a>,
+}
+
+#[derive(Serialize)]
+struct ReportNode<'a> {
+    name: &'a str,
+    size: i64,
+    /// x, y, width, height
+    rect: [f32; 4],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color_value: Option<i64>,
+    /// e.g. "added"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mark: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<ReportNode<'a>>,
+}
+
+impl<'a> ReportNode<'a> {
+    /// Nodes without a rect (too small to be arranged) are left out.
+    fn new(node: &'a Tree, heatmap: Option<&Heatmap>) -> Option<Self> {
+        let rect = node.rect?;
+        let color_value = heatmap.and_then(|heatmap| heatmap.value(&node.name));
+        let color = heatmap
+            .zip(color_value)
+            .map(|(heatmap, value)| super::to_hex(heatmap.color_of_value(value)));
+        Some(Self {
+            name: &node.name,
+            size: node.size(),
+            rect: [rect.x, rect.y, rect.w, rect.h],
+            color,
+            color_value,
+            mark: heatmap
+                .and_then(|heatmap| heatmap.mark(&node.name))
+                .map(Mark::name),
+            children: node
+                .children
+                .iter()
+                .filter_map(|child| ReportNode::new(child, heatmap))
+                .collect(),
+        })
+    }
+}
+
+/// Writes an already arranged tree as a single HTML file that can be opened in any browser,
+/// without this program. See `to_html`.
+pub fn save_html(
+    path: &Path,
+    tree: &Tree,
+    units: &str,
+    heatmap: Option<&Heatmap>,
+    width: f32,
+    height: f32,
+) -> Result<(), AnyError> {
+    fs::write(path, to_html(tree, units, heatmap, width, height)?)?;
+    Ok(())
+}
+
+/// The page embeds the tree with its rects as JSON, and a small script that draws it as an SVG
+/// that shows the path of the hovered node, selects nodes with clicks and searches them, like
+/// the window does.
+pub fn to_html(
+    tree: &Tree,
+    units: &str,
+    heatmap: Option<&Heatmap>,
+    width: f32,
+    height: f32,
+) -> Result<String, AnyError> {
+    let report = Report {
+        units,
+        color_units: heatmap.map(|heatmap| heatmap.units.as_str()),
+        width,
+        height,
+        path_colors: COLORS_HEX
+            .iter()
+            .map(|hex| format!("#{:06x}", hex))
+            .collect(),
+        tree: ReportNode::new(tree, heatmap).ok_or("the tree must be arranged to export it")?,
+    };
+    // a name like "</script>" would end the script early, and '<' can only appear inside
+    // JSON strings, where it can be escaped
+    let json = serde_json::to_string(&report)?.replace('<', "\\u003c");
+    Ok(TEMPLATE
+        .replace(TITLE_PLACEHOLDER, &escape_xml(&tree.name))
+        .replace(DATA_PLACEHOLDER, &json))
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::math::Rect;
+
+    use crate::arrangements::Arrangements;
+
+    use super::*;
+
+    #[test]
+    fn test_html() {
+        #[rustfmt::skip]
+        let mut tree = Tree::new_from_children("./".into(), vec![
+            Tree::new_from_children("./src".into(), vec![
+                Tree::new_from_size("./src/</script>.rs".into(), 1200),
+                Tree::new_from_size("./src/lib.rs".into(), 300),
+            ]),
+            Tree::new_from_size("./README.md".into(), 500),
+        ]);
+        let arrangement = Arrangements::Binary.new_arrangement(0.0);
+        arrangement.arrange(&mut tree, Rect::new(0.0, 0.0, 200.0, 100.0));
+
+        let html = to_html(&tree, "lines", None, 200.0, 100.0).unwrap();
+
+        assert!(!html.contains(TITLE_PLACEHOLDER));
+        assert!(!html.contains(DATA_PLACEHOLDER));
+        assert_eq!(
+            html.matches("</script>").count(),
+            TEMPLATE.matches("</script>").count()
+        );
+        assert!(html.contains(r#""name":"./src/\u003c/script>.rs","size":1200"#));
+        assert!(html.contains(r#""rect":[0.0,0.0,200.0,100.0]"#));
+    }
+
+    #[test]
+    fn test_unarranged_tree() {
+        let tree = Tree::new_from_size("./main.rs".into(), 1);
+        assert!(to_html(&tree, "lines", None, 200.0, 100.0).is_err());
+    }
+}
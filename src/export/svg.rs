@@ -0,0 +1,112 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use macroquad::color::{BLACK, LIGHTGRAY};
+
+use crate::export::{escape_xml, to_hex};
+use crate::heatmap::Heatmap;
+use crate::metrics::format_units;
+use crate::tree::Tree;
+use crate::AnyError;
+
+/// Writes an already arranged tree as an SVG image of `width` x `height` pixels. See `to_svg`.
+pub fn save_svg(
+    path: &Path,
+    tree: &Tree,
+    units: &str,
+    heatmap: Option<&Heatmap>,
+    width: f32,
+    height: f32,
+) -> Result<(), AnyError> {
+    fs::write(path, to_svg(tree, units, heatmap, width, height))?;
+    Ok(())
+}
+
+/// Each node is a `<g>` group with a `<rect>`, a `<title>` (shown as a tooltip by browsers) with
+/// its name and size, and the groups of its children. Nodes without a rect (too small to be
+/// arranged) are skipped. If there is a heatmap, the leaves are filled with its colors.
+pub fn to_svg(
+    tree: &Tree,
+    units: &str,
+    heatmap: Option<&Heatmap>,
+    width: f32,
+    height: f32,
+) -> String {
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    )
+    .unwrap();
+    writeln!(svg, r#"<g stroke="{}" stroke-width="1">"#, to_hex(BLACK)).unwrap();
+    write_node(&mut svg, tree, units, heatmap, 0);
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+fn write_node(svg: &mut String, node: &Tree, units: &str, heatmap: Option<&Heatmap>, depth: usize) {
+    let Some(rect) = node.rect else {
+        return;
+    };
+    let mut fill = LIGHTGRAY;
+    let mut title = format!("{}: {}", node.name, format_units(node.size(), units));
+    if let Some(heatmap) = heatmap {
+        if let Some(value) = heatmap.value(&node.name) {
+            fill = heatmap.color_of_value(value);
+            write!(title, ", {}", format_units(value, &heatmap.units)).unwrap();
+        }
+        if let Some(mark) = heatmap.mark(&node.name) {
+            write!(title, " ({})", mark.name()).unwrap();
+        }
+    }
+    let indent = "  ".repeat(depth);
+    writeln!(svg, "{indent}<g>").unwrap();
+    writeln!(
+        svg,
+        r#"{indent}  <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{}</title></rect>"#,
+        rect.x,
+        rect.y,
+        rect.w,
+        rect.h,
+        to_hex(fill),
+        escape_xml(&title)
+    )
+    .unwrap();
+    for child in &node.children {
+        write_node(svg, child, units, heatmap, depth + 1);
+    }
+    writeln!(svg, "{indent}</g>").unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::math::Rect;
+
+    use crate::arrangements::Arrangements;
+
+    use super::*;
+
+    #[test]
+    fn test_svg() {
+        #[rustfmt::skip]
+        let mut tree = Tree::new_from_children("./".into(), vec![
+            Tree::new_from_children("./src".into(), vec![
+                Tree::new_from_size("./src/<main>.rs".into(), 1200),
+                Tree::new_from_size("./src/lib.rs".into(), 300),
+            ]),
+            Tree::new_from_size("./README.md".into(), 500),
+        ]);
+        let arrangement = Arrangements::Binary.new_arrangement(0.0);
+        arrangement.arrange(&mut tree, Rect::new(0.0, 0.0, 200.0, 100.0));
+
+        let svg = to_svg(&tree, "lines", None, 200.0, 100.0);
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect ").count(), 5);
+        assert_eq!(svg.matches("<g>").count(), svg.matches("</g>").count() - 1);
+        assert!(svg.contains("<title>./src/&lt;main&gt;.rs: 1 200 lines</title>"));
+        assert!(svg.contains(r#"<rect x="0.0" y="0.0" width="200.0" height="100.0""#));
+    }
+}
@@ -1,38 +1,398 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
-use git2::{Repository, Tree};
-use macroquad::prelude::info;
+use git2::{Commit, Delta, Mailmap, Oid, Patch, Repository, RevparseMode, Revwalk, Sort, Tree};
+use macroquad::prelude::{info, warn};
+use serde::{Deserialize, Serialize};
 
+use crate::git_churn::cache::{is_cacheable, ChurnCache};
+use crate::progress::Progress;
 use crate::AnyError;
 
+mod cache;
+
 /// Represents a file and how many times it was changed in the whole git repo history
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct FileChurn {
     pub path: String,
     pub count: i32,
+    /// how many times each author (identified by their mailmap-resolved email) changed the file
+    pub authors: HashMap<String, i32>,
+    /// only computed if `ChurnOptions::count_lines` is set
+    pub lines_added: i64,
+    /// only computed if `ChurnOptions::count_lines` is set
+    pub lines_deleted: i64,
+}
+
+impl FileChurn {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            ..Default::default()
+        }
+    }
+
+    pub fn distinct_authors(&self) -> usize {
+        self.authors.len()
+    }
+
+    /// Percentage (0 to 100) of the changes that were done by the author that changed the file most
+    pub fn top_author_share(&self) -> i32 {
+        let top_author_count = self.authors.values().max().copied().unwrap_or(0);
+        if self.count == 0 {
+            0
+        } else {
+            ((100 * top_author_count as i64) / self.count as i64) as i32
+        }
+    }
+}
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+/// Diffing a few commits is so fast that it's not worth spawning threads for them
+const MIN_COMMITS_PER_THREAD: usize = 100;
+
+/// Which commits are considered when computing the churn
+#[derive(Clone, Debug, Default)]
+pub struct ChurnOptions {
+    /// maximum number of commits to process, starting from the most recent
+    pub max_commits: Option<usize>,
+    /// only commits from this day onwards, as seconds since epoch of the start of the day (UTC)
+    pub since: Option<i64>,
+    /// only commits up to this day, inclusive, as seconds since epoch of the start of the day (UTC)
+    pub until: Option<i64>,
+    /// a revision range like `v1.0..v2.0`, `main...feature` or a single revision. HEAD if None
+    pub range: Option<String>,
+    /// only commits whose author (after applying the .mailmap) contains this text, ignoring case.
+    /// The text is compared against the "Name <email>" of the author.
+    pub author: Option<String>,
+    /// drop the files that don't exist in the last commit of the range (HEAD if there is no range),
+    /// e.g. deleted files
+    pub drop_deleted: bool,
+    /// count the lines added and deleted in each file. This is slower because it requires
+    /// diffing the content of the files instead of only comparing the trees of the commits
+    pub count_lines: bool,
+    /// store the churn in the .git folder, and next time only process the commits added since then
+    pub use_cache: bool,
+    /// counts the processed commits, and stops the computation if it's cancelled
+    pub progress: Arc<Progress>,
+}
+
+/// Churn accumulated while walking the history from the most recent commit to the oldest.
+#[derive(Default)]
+struct ChurnAccumulator {
+    files: HashMap<String, FileChurn>,
+    /// Maps old paths to the path that the file has in the most recent commit, so that changes
+    /// done before a rename are attributed to the current path.
+    renames: HashMap<String, String>,
 }
 
-pub fn print_git_churn(path: PathBuf, max_commits: Option<usize>) -> Result<(), AnyError> {
-    let mut files_and_counts = git_churn(path, max_commits)?;
+pub fn print_git_churn(path: PathBuf, options: &ChurnOptions) -> Result<(), AnyError> {
+    let mut files_and_counts = git_churn(path, options)?;
     files_and_counts.sort_by(|a, b| a.count.cmp(&b.count));
-    for FileChurn { path, count } in files_and_counts {
+    for FileChurn { path, count, .. } in files_and_counts {
         println!("{:>5} {}", count, path);
     }
     Ok(())
 }
 
-pub fn git_churn(path: PathBuf, max_commits: Option<usize>) -> Result<Vec<FileChurn>, AnyError> {
+pub fn git_churn(path: PathBuf, options: &ChurnOptions) -> Result<Vec<FileChurn>, AnyError> {
     let repo = Repository::open(path)?;
-    let mut revwalk = repo.revwalk()?;
-    revwalk.push_head()?;
+    let (mut revwalk, tips, hidden) = create_revwalk(&repo, options.range.as_deref())?;
+    let cache = if is_cacheable(options) {
+        ChurnCache::load(&repo, options, &tips, &hidden)
+    } else {
+        None
+    };
+    if let Some(cache) = &cache {
+        info!("Using the churn cache, only new commits will be processed");
+        for cached_tip in cache.tips()? {
+            revwalk.hide(cached_tip)?;
+        }
+    }
 
-    let mut files_changed_count = HashMap::new();
-    let mut commit_count = 0;
-    let log_period = 1000;
+    let mut churn = walk_commits(&repo, revwalk, options)?;
+
+    if let Some(cache) = cache {
+        for file_churn in cache.files {
+            churn.add_older_churn(file_churn);
+        }
+    }
+    let mut files = churn.files.into_values().collect::<Vec<_>>();
+    if is_cacheable(options) {
+        if let Err(e) =
+            ChurnCache::new(&repo, options, &tips, &hidden, files.clone()).save(&repo, options)
+        {
+            warn!("Could not save the churn cache: {}", e);
+        }
+    }
+    if options.drop_deleted {
+        // the last tip is HEAD, or the end of the range (the `B` of `A..B` and `A...B`)
+        let tip = tips.last().ok_or("there are no commits to walk")?;
+        let tip_tree = repo.find_commit(*tip)?.tree()?;
+        files.retain(|file_churn| tip_tree.get_path(Path::new(&file_churn.path)).is_ok());
+    }
+    Ok(files)
+}
+
+fn walk_commits(
+    repo: &Repository,
+    revwalk: Revwalk,
+    options: &ChurnOptions,
+) -> Result<ChurnAccumulator, AnyError> {
+    let commits = select_commits(repo, revwalk, options)?;
+    options.progress.set_total_commits(commits.len());
+    info!(
+        "About to process {} commits diffs. This may take a few seconds...",
+        commits.len()
+    );
+    let chunks = diff_commits_in_parallel(repo.path(), &commits, options)?;
+
+    // the chunks are sorted from the most recent commits to the oldest, like the commits inside
+    // each chunk, so merging them in order gives the same result as processing all sequentially
+    let mut churn = ChurnAccumulator::default();
+    for chunk in chunks {
+        churn.add_older_chunk(chunk);
+    }
+    info!("Total commits processed: {}", commits.len());
+    Ok(churn)
+}
+
+/// A commit to be diffed, with the identifier of its author.
+struct SelectedCommit {
+    oid: Oid,
+    author: String,
+}
 
-    info!("About to process commits diffs. This may take a few seconds...");
+fn select_commits(
+    repo: &Repository,
+    revwalk: Revwalk,
+    options: &ChurnOptions,
+) -> Result<Vec<SelectedCommit>, AnyError> {
+    let mailmap = repo.mailmap()?;
+    let author_filter = options.author.as_ref().map(|author| author.to_lowercase());
+    let mut commits = Vec::new();
     for oid in revwalk {
+        options.progress.check_cancelled()?;
         let commit = repo.find_commit(oid?)?;
+        if !is_in_time_window(&commit, options) {
+            continue;
+        }
+        let (author, author_matches) = get_author(&commit, &mailmap, author_filter.as_deref())?;
+        if !author_matches {
+            continue;
+        }
+        commits.push(SelectedCommit {
+            oid: commit.id(),
+            author,
+        });
+        if let Some(max) = options.max_commits {
+            if commits.len() >= max {
+                break;
+            }
+        }
+    }
+    Ok(commits)
+}
+
+/// Splits the commits in chunks that are diffed by several threads, each with its own
+/// `Repository`. Returns the churn of each chunk, in the same order as the commits.
+fn diff_commits_in_parallel(
+    repo_path: &Path,
+    commits: &[SelectedCommit],
+    options: &ChurnOptions,
+) -> Result<Vec<ChurnAccumulator>, AnyError> {
+    let thread_count = std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1)
+        .min(commits.len() / MIN_COMMITS_PER_THREAD)
+        .max(1);
+    // several chunks per thread, so that a thread that gets easy commits can take more chunks
+    let chunk_size = commits.len().div_ceil(thread_count * 4).max(1);
+    let chunks = commits.chunks(chunk_size).collect::<Vec<_>>();
+    let next_chunk = AtomicUsize::new(0);
+    let commit_count = AtomicUsize::new(0);
+    let log_period = 1000;
+
+    let diff_chunks = || -> Result<Vec<(usize, ChurnAccumulator)>, AnyError> {
+        let repo = Repository::open(repo_path)?;
+        let mut diffed = Vec::new();
+        loop {
+            let index = next_chunk.fetch_add(1, Ordering::Relaxed);
+            let Some(chunk) = chunks.get(index) else {
+                return Ok(diffed);
+            };
+            let mut churn = ChurnAccumulator::default();
+            for selected in *chunk {
+                options.progress.check_cancelled()?;
+                churn.add_commit(&repo, selected, options)?;
+                options.progress.add_commit();
+                let count = commit_count.fetch_add(1, Ordering::Relaxed) + 1;
+                if count % log_period == 0 {
+                    info!(
+                        "Still processing commits... Processed commits so far: {}",
+                        count
+                    );
+                }
+            }
+            diffed.push((index, churn));
+        }
+    };
+
+    let mut diffed_chunks = std::thread::scope(|scope| {
+        let handles = (0..thread_count)
+            .map(|_| scope.spawn(|| diff_chunks().map_err(|e| e.to_string())))
+            .collect::<Vec<_>>();
+        let mut diffed_chunks = Vec::new();
+        for handle in handles {
+            let diffed = handle
+                .join()
+                .map_err(|_| "a thread diffing commits panicked".to_string())??;
+            diffed_chunks.extend(diffed);
+        }
+        Ok::<_, String>(diffed_chunks)
+    })?;
+    diffed_chunks.sort_by_key(|(index, _)| *index);
+    Ok(diffed_chunks.into_iter().map(|(_, churn)| churn).collect())
+}
+
+/// Returns the revwalk, the commits where it starts and the commits it hides.
+fn create_revwalk<'repo>(
+    repo: &'repo Repository,
+    range: Option<&str>,
+) -> Result<(Revwalk<'repo>, Vec<Oid>, Vec<Oid>), AnyError> {
+    let mut revwalk = repo.revwalk()?;
+    // children before parents, so that renames are found before the changes of the old paths
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    let mut hidden = Vec::new();
+    let tips = if let Some(range) = range {
+        let revspec = repo.revparse(range)?;
+        let commit_id = |object: Option<&git2::Object>| -> Result<Oid, AnyError> {
+            Ok(object.ok_or("incomplete range")?.peel_to_commit()?.id())
+        };
+        if revspec.mode().contains(RevparseMode::MERGE_BASE) {
+            let (from, to) = (commit_id(revspec.from())?, commit_id(revspec.to())?);
+            hidden.push(repo.merge_base(from, to)?);
+            vec![from, to]
+        } else if revspec.mode().contains(RevparseMode::RANGE) {
+            hidden.push(commit_id(revspec.from())?);
+            vec![commit_id(revspec.to())?]
+        } else {
+            vec![commit_id(revspec.from())?]
+        }
+    } else {
+        vec![repo.head()?.peel_to_commit()?.id()]
+    };
+    for tip in &tips {
+        revwalk.push(*tip)?;
+    }
+    for commit in &hidden {
+        revwalk.hide(*commit)?;
+    }
+    Ok((revwalk, tips, hidden))
+}
+
+fn is_in_time_window(commit: &Commit, options: &ChurnOptions) -> bool {
+    let time = commit.time().seconds();
+    let before_since = options.since.is_some_and(|since| time < since);
+    let after_until = options
+        .until
+        .is_some_and(|until| time >= until + SECONDS_PER_DAY);
+    !before_since && !after_until
+}
+
+/// Parses a date like "2023-12-31" into the seconds since epoch at the start of that day (UTC).
+pub fn parse_date(date: &str) -> Result<i64, String> {
+    let error = || format!("Invalid date '{}', expected a format like 2023-12-31", date);
+    let parts = date
+        .trim()
+        .split('-')
+        .map(|part| part.parse::<i64>().map_err(|_| error()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if let [year, month, day] = parts[..] {
+        if (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day) {
+            return Ok(days_from_civil(year, month, day) * SECONDS_PER_DAY);
+        }
+    }
+    Err(error())
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        2 if is_leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Formats the seconds since epoch as the date of that moment (UTC), like "2023-12-31".
+pub fn format_date(seconds: i64) -> String {
+    let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Inverse of `days_from_civil`.
+/// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = if days >= 0 { days } else { days - 146096 } / 146097;
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Days since 1970-01-01 of a date in the proleptic gregorian calendar.
+/// See http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Returns the identifier of the author of the commit, and whether it matches the filter.
+fn get_author(
+    commit: &Commit,
+    mailmap: &Mailmap,
+    author_filter: Option<&str>,
+) -> Result<(String, bool), AnyError> {
+    let signature = commit.author_with_mailmap(mailmap)?;
+    let name = String::from_utf8_lossy(signature.name_bytes());
+    let email = String::from_utf8_lossy(signature.email_bytes());
+    let matches = match author_filter {
+        Some(filter) => format!("{} <{}>", name, email)
+            .to_lowercase()
+            .contains(filter),
+        None => true,
+    };
+    let author = if email.is_empty() { name } else { email };
+    Ok((author.to_lowercase(), matches))
+}
+
+impl ChurnAccumulator {
+    fn add_commit(
+        &mut self,
+        repo: &Repository,
+        selected: &SelectedCommit,
+        options: &ChurnOptions,
+    ) -> Result<(), AnyError> {
+        let commit = repo.find_commit(selected.oid)?;
         let tree = commit.tree()?;
 
         // I couldn't find any way to list the modified files in a commit without doing
@@ -41,53 +401,119 @@ pub fn git_churn(path: PathBuf, max_commits: Option<usize>) -> Result<Vec<FileCh
         // of https://git-scm.com/book/en/v2/Git-Internals-Git-Objects. Mindblown.
         for parent in commit.parents() {
             let parent_tree = parent.tree()?;
-            add_diff(&tree, Some(&parent_tree), &repo, &mut files_changed_count)?;
+            self.add_diff(&tree, Some(&parent_tree), repo, &selected.author, options)?;
         }
 
         if commit.parent_count() == 0 {
-            add_diff(&tree, None, &repo, &mut files_changed_count)?;
+            self.add_diff(&tree, None, repo, &selected.author, options)?;
         }
+        Ok(())
+    }
 
-        commit_count += 1;
-        if commit_count % log_period == 0 {
-            info!(
-                "Still processing commits... Processed commits so far: {}",
-                commit_count
-            );
+    fn add_diff(
+        &mut self,
+        commit_tree: &Tree,
+        parent_tree: Option<&Tree>,
+        repo: &Repository,
+        author: &str,
+        options: &ChurnOptions,
+    ) -> Result<(), AnyError> {
+        let mut diff = repo.diff_tree_to_tree(parent_tree, Some(commit_tree), None)?;
+        diff.find_similar(None)?;
+        for (i, delta) in diff.deltas().enumerate() {
+            let new_path = path_to_string(delta.new_file().path_bytes())?;
+            let old_path = if delta.status() == Delta::Renamed {
+                Some(path_to_string(delta.old_file().path_bytes())?)
+            } else {
+                None
+            };
+            let lines = if options.count_lines {
+                count_lines_changed(&diff, i)?
+            } else {
+                LinesChanged::default()
+            };
+            self.add_change(new_path, old_path, author, lines);
         }
-        if let Some(max) = max_commits {
-            if commit_count >= max {
-                break;
+        Ok(())
+    }
+
+    fn add_change(
+        &mut self,
+        new_path: String,
+        renamed_from: Option<String>,
+        author: &str,
+        lines: LinesChanged,
+    ) {
+        let current_path = self.renames.get(&new_path).cloned().unwrap_or(new_path);
+        if let Some(old_path) = renamed_from {
+            if old_path != current_path {
+                self.renames.insert(old_path, current_path.clone());
             }
         }
+        self.add_file(current_path, author, lines);
+    }
+
+    /// Adds the churn and renames of a chunk of commits older than the ones processed so far.
+    fn add_older_chunk(&mut self, older: ChurnAccumulator) {
+        for file_churn in older.files.into_values() {
+            self.add_older_churn(file_churn);
+        }
+        for (old_path, path_in_chunk) in older.renames {
+            let current_path = self.renames.get(&path_in_chunk).cloned();
+            let current_path = current_path.unwrap_or(path_in_chunk);
+            self.renames.insert(old_path, current_path);
+        }
+    }
+
+    /// Adds the churn of commits older than the ones processed so far, e.g. from the cache.
+    fn add_older_churn(&mut self, older: FileChurn) {
+        let current_path = self.renames.get(&older.path).cloned();
+        let path = current_path.unwrap_or(older.path);
+        let file_churn = self
+            .files
+            .entry(path.clone())
+            .or_insert_with(|| FileChurn::new(path));
+        file_churn.count += older.count;
+        for (author, count) in older.authors {
+            *file_churn.authors.entry(author).or_insert(0) += count;
+        }
+        file_churn.lines_added += older.lines_added;
+        file_churn.lines_deleted += older.lines_deleted;
+    }
+
+    fn add_file(&mut self, path: String, author: &str, lines: LinesChanged) {
+        let file_churn = self
+            .files
+            .entry(path.clone())
+            .or_insert_with(|| FileChurn::new(path));
+        file_churn.count += 1;
+        *file_churn.authors.entry(author.to_string()).or_insert(0) += 1;
+        file_churn.lines_added += lines.added;
+        file_churn.lines_deleted += lines.deleted;
     }
-    info!("Total commits processed: {}", commit_count);
-    Ok(files_changed_count
-        .into_iter()
-        .map(|(path, count)| FileChurn { path, count })
-        .collect::<Vec<_>>())
 }
 
-fn add_diff(
-    commit_tree: &Tree,
-    parent_tree: Option<&Tree>,
-    repo: &Repository,
-    files_changed_count: &mut HashMap<String, i32>,
-) -> Result<(), AnyError> {
-    let mut diff = repo.diff_tree_to_tree(parent_tree, Some(commit_tree), None)?;
-    diff.find_similar(None)?;
-    for delta in diff.deltas() {
-        let new_file = delta.new_file();
-        let bytes = new_file.path_bytes().unwrap();
-        let path_string = String::from_utf8(bytes.to_vec())?;
-        add_file(path_string, files_changed_count);
+#[derive(Default, Copy, Clone)]
+struct LinesChanged {
+    added: i64,
+    deleted: i64,
+}
+
+/// Binary files count as 0 lines changed.
+fn count_lines_changed(diff: &git2::Diff, delta_index: usize) -> Result<LinesChanged, AnyError> {
+    if let Some(patch) = Patch::from_diff(diff, delta_index)? {
+        let (_context, added, deleted) = patch.line_stats()?;
+        Ok(LinesChanged {
+            added: added as i64,
+            deleted: deleted as i64,
+        })
+    } else {
+        Ok(LinesChanged::default())
     }
-    Ok(())
 }
 
-fn add_file(path: String, files_changed_count: &mut HashMap<String, i32>) {
-    let count = files_changed_count.entry(path).or_insert(0);
-    *count += 1;
+fn path_to_string(bytes: Option<&[u8]>) -> Result<String, AnyError> {
+    Ok(String::from_utf8(bytes.unwrap().to_vec())?)
 }
 
 #[cfg(test)]
@@ -98,6 +524,133 @@ mod tests {
     #[test]
     #[ignore]
     fn test_print_git_churn() {
-        print_git_churn(".".into(), None).unwrap();
+        print_git_churn(".".into(), &ChurnOptions::default()).unwrap();
+    }
+
+    #[test]
+    fn test_authors() {
+        let mut churn = ChurnAccumulator::default();
+        let no_lines = LinesChanged::default();
+        churn.add_file("main.rs".to_string(), "alice@a.com", no_lines);
+        churn.add_file("main.rs".to_string(), "bob@b.com", no_lines);
+        churn.add_file("main.rs".to_string(), "alice@a.com", no_lines);
+        churn.add_file("lib.rs".to_string(), "bob@b.com", no_lines);
+
+        let files = churn.files;
+        let main = &files["main.rs"];
+        assert_eq!(main.count, 3);
+        assert_eq!(main.distinct_authors(), 2);
+        assert_eq!(main.top_author_share(), 66);
+        let lib = &files["lib.rs"];
+        assert_eq!(lib.distinct_authors(), 1);
+        assert_eq!(lib.top_author_share(), 100);
+    }
+
+    #[test]
+    fn test_renames() {
+        let mut churn = ChurnAccumulator::default();
+        let lines = LinesChanged {
+            added: 2,
+            deleted: 1,
+        };
+        // from the most recent commit to the oldest
+        churn.add_change("src/c.rs".to_string(), None, "", lines);
+        churn.add_change(
+            "src/c.rs".to_string(),
+            Some("src/b.rs".to_string()),
+            "",
+            lines,
+        );
+        churn.add_change("src/b.rs".to_string(), None, "", lines);
+        churn.add_change("src/b.rs".to_string(), Some("a.rs".to_string()), "", lines);
+        churn.add_change("a.rs".to_string(), None, "", lines);
+        churn.add_change("other.rs".to_string(), None, "", lines);
+
+        let files = churn.files;
+        assert_eq!(files.len(), 2);
+        assert_eq!(files["src/c.rs"].count, 5);
+        assert_eq!(files["src/c.rs"].lines_added, 10);
+        assert_eq!(files["src/c.rs"].lines_deleted, 5);
+        assert_eq!(files["other.rs"].count, 1);
+    }
+
+    #[test]
+    fn test_add_older_churn_after_rename() {
+        let mut churn = ChurnAccumulator::default();
+        let lines = LinesChanged::default();
+        churn.add_change(
+            "new.rs".to_string(),
+            Some("old.rs".to_string()),
+            "bob",
+            lines,
+        );
+        let mut older = FileChurn::new("old.rs".to_string());
+        older.count = 3;
+        older.authors.insert("alice".to_string(), 3);
+
+        churn.add_older_churn(older);
+
+        let files = churn.files;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files["new.rs"].count, 4);
+        assert_eq!(files["new.rs"].distinct_authors(), 2);
+    }
+
+    #[test]
+    fn test_chunks_give_same_result_as_sequential() {
+        let lines = LinesChanged::default();
+        #[rustfmt::skip]
+        let changes = [
+            ("c.rs", None),
+            ("c.rs", Some("b.rs")),
+            ("b.rs", None),
+            ("other.rs", None),
+            ("b.rs", Some("a.rs")),
+            ("a.rs", None),
+        ];
+        let mut sequential = ChurnAccumulator::default();
+        for (path, renamed_from) in changes {
+            let renamed_from = renamed_from.map(|s| s.to_string());
+            sequential.add_change(path.to_string(), renamed_from, "", lines);
+        }
+        for split in 0..changes.len() {
+            let mut chunked = ChurnAccumulator::default();
+            for chunk in changes.chunks(split + 1) {
+                let mut churn = ChurnAccumulator::default();
+                for (path, renamed_from) in chunk {
+                    let renamed_from = renamed_from.map(|s| s.to_string());
+                    churn.add_change(path.to_string(), renamed_from, "", lines);
+                }
+                chunked.add_older_chunk(churn);
+            }
+            assert_eq!(chunked.files.len(), sequential.files.len());
+            for (path, file_churn) in &sequential.files {
+                assert_eq!(chunked.files[path].count, file_churn.count, "{}", path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("1970-01-01"), Ok(0));
+        assert_eq!(parse_date("1970-01-02"), Ok(SECONDS_PER_DAY));
+        assert_eq!(parse_date("2000-03-01"), Ok(951868800));
+        assert_eq!(parse_date("2024-02-29"), Ok(1709164800));
+        assert!(parse_date("2024-13-01").is_err());
+        assert!(parse_date("2023-02-29").is_err());
+        assert!(parse_date("2024-02-31").is_err());
+        assert!(parse_date("2023-04-31").is_err());
+        assert!(parse_date("2000-02-29").is_ok());
+        assert!(parse_date("1900-02-29").is_err());
+        assert!(parse_date("2024-02").is_err());
+        assert!(parse_date("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951868800 + 3600), "2000-03-01");
+        assert_eq!(format_date(1709164800), "2024-02-29");
+        assert_eq!(format_date(-1), "1969-12-31");
     }
 }
@@ -0,0 +1,208 @@
+use std::fs;
+use std::path::PathBuf;
+
+use git2::{Oid, Repository};
+use macroquad::prelude::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::git_churn::{ChurnOptions, FileChurn};
+use crate::AnyError;
+
+const CACHE_FILE_PREFIX: &str = "code-map-churn-cache-";
+const CACHE_VERSION: u32 = 2;
+
+/// The churn of all the commits reachable from some tips, stored in the .git folder so that the
+/// next time only the commits newer than those tips have to be processed.
+#[derive(Serialize, Deserialize)]
+pub(super) struct ChurnCache {
+    version: u32,
+    /// the options that affect the counts. The cache is only valid for the same options
+    key: String,
+    /// the commits where the walk started
+    tips: Vec<String>,
+    /// the commits hidden from the walk, e.g. the `A` of a `A..B` range. If they move, the cached
+    /// counts include commits that are not in the range anymore
+    hidden: Vec<String>,
+    pub files: Vec<FileChurn>,
+}
+
+/// A cache is only possible if adding newer commits gives the same result as processing them
+/// all again. That's not the case if we only want the last N commits, or if the merge base of
+/// a `A...B` range can move.
+pub(super) fn is_cacheable(options: &ChurnOptions) -> bool {
+    let symmetric_range = options
+        .range
+        .as_ref()
+        .is_some_and(|range| range.contains("..."));
+    options.use_cache && options.max_commits.is_none() && !symmetric_range
+}
+
+impl ChurnCache {
+    pub fn new(
+        repo: &Repository,
+        options: &ChurnOptions,
+        tips: &[Oid],
+        hidden: &[Oid],
+        files: Vec<FileChurn>,
+    ) -> Self {
+        Self {
+            version: CACHE_VERSION,
+            key: cache_key(repo, options),
+            tips: to_strings(tips),
+            hidden: to_strings(hidden),
+            files,
+        }
+    }
+
+    /// Returns None if there's no cache, or if it was computed with other options, or if the
+    /// history was rewritten (the cached tips are not ancestors of the new tips anymore), or if the
+    /// hidden side of the range moved.
+    pub fn load(
+        repo: &Repository,
+        options: &ChurnOptions,
+        new_tips: &[Oid],
+        new_hidden: &[Oid],
+    ) -> Option<Self> {
+        let content = fs::read_to_string(cache_path(repo, options)).ok()?;
+        let cache: ChurnCache = match serde_json::from_str(&content) {
+            Ok(cache) => cache,
+            Err(e) => {
+                warn!("Ignoring unreadable churn cache: {}", e);
+                return None;
+            }
+        };
+        if cache.version != CACHE_VERSION || cache.key != cache_key(repo, options) {
+            info!("The churn cache was computed with other options, recomputing...");
+            None
+        } else if cache.hidden != to_strings(new_hidden) {
+            info!(
+                "The start of the range changed since the churn cache was computed, recomputing..."
+            );
+            None
+        } else if !cache.is_ancestor_of(repo, new_tips) {
+            info!("The history changed since the churn cache was computed, recomputing...");
+            None
+        } else {
+            Some(cache)
+        }
+    }
+
+    pub fn save(&self, repo: &Repository, options: &ChurnOptions) -> Result<(), AnyError> {
+        fs::write(cache_path(repo, options), serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn tips(&self) -> Result<Vec<Oid>, AnyError> {
+        let mut tips = Vec::new();
+        for tip in &self.tips {
+            tips.push(Oid::from_str(tip)?);
+        }
+        Ok(tips)
+    }
+
+    fn is_ancestor_of(&self, repo: &Repository, new_tips: &[Oid]) -> bool {
+        let Ok(tips) = self.tips() else {
+            return false;
+        };
+        tips.iter().all(|tip| {
+            new_tips.iter().any(|new_tip| {
+                new_tip == tip || repo.graph_descendant_of(*new_tip, *tip).unwrap_or(false)
+            })
+        })
+    }
+}
+
+/// The .mailmap is part of the key because it changes the authors, and it can be edited without
+/// committing it.
+fn cache_key(repo: &Repository, options: &ChurnOptions) -> String {
+    format!(
+        "since={:?} until={:?} range={:?} author={:?} count_lines={} mailmap={:?}",
+        options.since,
+        options.until,
+        options.range,
+        options.author,
+        options.count_lines,
+        mailmap_hash(repo).map(|hash| format!("{:016x}", hash))
+    )
+}
+
+/// Hash of the .mailmap that `Repository::mailmap` reads: the one in the working directory, or
+/// the one in HEAD for bare repos. None if there's no .mailmap.
+fn mailmap_hash(repo: &Repository) -> Option<u64> {
+    match repo.workdir() {
+        Some(workdir) => Some(fnv1a(&fs::read(workdir.join(".mailmap")).ok()?)),
+        None => {
+            let blob = repo
+                .revparse_single("HEAD:.mailmap")
+                .ok()?
+                .peel_to_blob()
+                .ok()?;
+            Some(fnv1a(blob.content()))
+        }
+    }
+}
+
+/// Each set of options has its own file, so that computing several metrics with different
+/// options (e.g. `--color-metric`) doesn't overwrite the cache of the others.
+fn cache_path(repo: &Repository, options: &ChurnOptions) -> PathBuf {
+    let file_name = format!(
+        "{}{:016x}.json",
+        CACHE_FILE_PREFIX,
+        fnv1a(cache_key(repo, options).as_bytes())
+    );
+    repo.path().join(file_name)
+}
+
+/// A hash that doesn't change between runs or versions of Rust, unlike the `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn to_strings(oids: &[Oid]) -> Vec<String> {
+    oids.iter().map(|oid| oid.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_repo::TestRepo;
+
+    #[test]
+    fn test_cache_per_options() {
+        let test_repo = TestRepo::new("cache-options");
+        let repo = &test_repo.repo;
+        let options = ChurnOptions::default();
+        let count_lines = ChurnOptions {
+            count_lines: true,
+            ..Default::default()
+        };
+
+        assert_ne!(cache_path(repo, &options), cache_path(repo, &count_lines));
+        assert_eq!(
+            cache_path(repo, &options),
+            cache_path(repo, &options.clone())
+        );
+    }
+
+    #[test]
+    fn test_cache_per_mailmap() {
+        let mut test_repo = TestRepo::new("cache-mailmap");
+        test_repo.commit(&[("README.md", "# test\n")], "readme");
+        let options = ChurnOptions::default();
+        let without_mailmap = cache_path(&test_repo.repo, &options);
+
+        fs::write(test_repo.path.join(".mailmap"), "Test <test@example.com>\n").unwrap();
+        let with_mailmap = cache_path(&test_repo.repo, &options);
+        fs::write(
+            test_repo.path.join(".mailmap"),
+            "Other <test@example.com>\n",
+        )
+        .unwrap();
+        let with_edited_mailmap = cache_path(&test_repo.repo, &options);
+
+        assert_ne!(without_mailmap, with_mailmap);
+        assert_ne!(with_mailmap, with_edited_mailmap);
+    }
+}
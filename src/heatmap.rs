@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use macroquad::color::Color;
+
+use crate::tree::{relative_path, Tree};
+
+/// Colors from the lowest to the highest value, from pale yellow to dark red.
+const GRADIENT: [Color; 3] = [
+    Color::new(1.0, 0.96, 0.7, 1.0),
+    Color::new(0.99, 0.6, 0.3, 1.0),
+    Color::new(0.75, 0.1, 0.1, 1.0),
+];
+/// Colors from the most negative to the most positive value, e.g. from shrinkage to growth.
+const DIVERGING_GRADIENT: [Color; 3] = [
+    Color::new(0.8, 0.2, 0.2, 1.0),
+    Color::new(0.95, 0.95, 0.9, 1.0),
+    Color::new(0.2, 0.65, 0.3, 1.0),
+];
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Scale {
+    /// from 0 to the max value
+    Logarithmic,
+    /// from -max to max, with 0 in the middle
+    Diverging,
+}
+
+/// Something special about a leaf that its color doesn't tell.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Mark {
+    Added,
+    Removed,
+}
+
+impl Mark {
+    pub fn name(self) -> &'static str {
+        match self {
+            Mark::Added => "added",
+            Mark::Removed => "removed",
+        }
+    }
+}
+
+/// The values of a second metric (e.g. churn), to color the leaves of a tree whose sizes come
+/// from another metric (e.g. lines), so that hotspots like big files that change often stand out.
+pub struct Heatmap {
+    pub units: String,
+    /// keyed by the name of the leaf in the main tree
+    values: HashMap<String, i64>,
+    marks: HashMap<String, Mark>,
+    /// the highest absolute value
+    pub max: i64,
+    pub scale: Scale,
+}
+
+impl Heatmap {
+    /// The leaves of both trees are matched by their path relative to the root of each tree.
+    /// Leaves of the main tree that are not in the color tree have no color.
+    pub fn new(tree: &Tree, color_tree: &Tree, units: &str) -> Self {
+        let color_values = color_tree.leaf_sizes_by_path();
+        let mut values = HashMap::new();
+        add_values(tree, &tree.name, &color_values, &mut values);
+        Self::from_values(values, HashMap::new(), units, Scale::Logarithmic)
+    }
+
+    /// `values` and `marks` are keyed by the name of the leaves in the main tree.
+    pub fn from_values(
+        values: HashMap<String, i64>,
+        marks: HashMap<String, Mark>,
+        units: &str,
+        scale: Scale,
+    ) -> Self {
+        let max = values.values().map(|value| value.abs()).max().unwrap_or(0);
+        Self {
+            units: units.to_string(),
+            values,
+            marks,
+            max,
+            scale,
+        }
+    }
+
+    pub fn value(&self, name: &str) -> Option<i64> {
+        self.values.get(name).copied()
+    }
+
+    pub fn color(&self, name: &str) -> Option<Color> {
+        self.value(name).map(|value| self.color_of_value(value))
+    }
+
+    pub fn mark(&self, name: &str) -> Option<Mark> {
+        self.marks.get(name).copied()
+    }
+
+    /// Uses a logarithmic scale, because metrics like churn usually have a few huge values.
+    pub fn color_of_value(&self, value: i64) -> Color {
+        let fraction = if self.max <= 0 {
+            0.0
+        } else {
+            ((value.unsigned_abs() as f64).ln_1p() / (self.max as f64).ln_1p()) as f32
+        };
+        match self.scale {
+            Scale::Logarithmic => gradient(if value < 0 { 0.0 } else { fraction }),
+            Scale::Diverging => diverging_gradient(fraction.copysign(value as f32)),
+        }
+    }
+
+    /// `fraction` goes from 0 (the left of the legend, the lowest value) to 1 (the right).
+    pub fn legend_color(&self, fraction: f32) -> Color {
+        match self.scale {
+            Scale::Logarithmic => gradient(fraction),
+            Scale::Diverging => diverging_gradient(fraction * 2.0 - 1.0),
+        }
+    }
+
+    /// The value at the left of the legend.
+    pub fn min(&self) -> i64 {
+        match self.scale {
+            Scale::Logarithmic => 0,
+            Scale::Diverging => -self.max,
+        }
+    }
+}
+
+fn add_values(
+    node: &Tree,
+    root: &str,
+    color_values: &HashMap<String, i64>,
+    values: &mut HashMap<String, i64>,
+) {
+    if node.children.is_empty() {
+        if let Some(value) = color_values.get(&relative_path(root, &node.name)) {
+            values.insert(node.name.clone(), *value);
+        }
+    } else {
+        for child in &node.children {
+            add_values(child, root, color_values, values);
+        }
+    }
+}
+
+/// `fraction` goes from 0 (lowest value) to 1 (highest value).
+pub fn gradient(fraction: f32) -> Color {
+    interpolate(&GRADIENT, fraction)
+}
+
+/// `fraction` goes from -1 (most negative value) to 1 (most positive value).
+pub fn diverging_gradient(fraction: f32) -> Color {
+    interpolate(&DIVERGING_GRADIENT, (fraction + 1.0) * 0.5)
+}
+
+fn interpolate(colors: &[Color], fraction: f32) -> Color {
+    let position = fraction.clamp(0.0, 1.0) * (colors.len() - 1) as f32;
+    let index = (position as usize).min(colors.len() - 2);
+    let t = position - index as f32;
+    let (from, to) = (colors[index], colors[index + 1]);
+    Color::new(
+        from.r * (1.0 - t) + to.r * t,
+        from.g * (1.0 - t) + to.g * t,
+        from.b * (1.0 - t) + to.b * t,
+        1.0,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_by_path() {
+        #[rustfmt::skip]
+        let lines = Tree::new_from_children(".".into(), vec![
+            Tree::new_from_children("./src".into(), vec![
+                Tree::new_from_size("./src/main.rs".into(), 100),
+                Tree::new_from_size("./src/uncommitted.rs".into(), 20),
+            ]),
+        ]);
+        #[rustfmt::skip]
+        let churn = Tree::new_from_children("./".into(), vec![
+            Tree::new_from_children("./src".into(), vec![
+                Tree::new_from_size("./src/main.rs".into(), 7),
+                Tree::new_from_size("./src/deleted.rs".into(), 3),
+            ]),
+        ]);
+
+        let heatmap = Heatmap::new(&lines, &churn, "commits");
+
+        assert_eq!(heatmap.value("./src/main.rs"), Some(7));
+        assert_eq!(heatmap.value("./src/uncommitted.rs"), None);
+        assert_eq!(heatmap.max, 7);
+        assert_eq!(heatmap.color_of_value(7), GRADIENT[2]);
+        assert_eq!(heatmap.color_of_value(0), GRADIENT[0]);
+    }
+
+    #[test]
+    fn test_gradient() {
+        assert_eq!(gradient(0.0), GRADIENT[0]);
+        assert_eq!(gradient(0.5), GRADIENT[1]);
+        assert_eq!(gradient(1.0), GRADIENT[2]);
+        assert_eq!(gradient(2.0), GRADIENT[2]);
+    }
+
+    #[test]
+    fn test_diverging() {
+        let values = HashMap::from([
+            ("./grown.rs".to_string(), 10),
+            ("./shrunk.rs".to_string(), -20),
+        ]);
+        let heatmap = Heatmap::from_values(values, HashMap::new(), "lines", Scale::Diverging);
+
+        assert_eq!(heatmap.max, 20);
+        assert_eq!(heatmap.min(), -20);
+        assert_eq!(heatmap.color_of_value(-20), DIVERGING_GRADIENT[0]);
+        assert_eq!(heatmap.color_of_value(0), DIVERGING_GRADIENT[1]);
+        assert_eq!(heatmap.legend_color(1.0), DIVERGING_GRADIENT[2]);
+        assert!(heatmap.color("./grown.rs").unwrap().g > heatmap.color("./grown.rs").unwrap().r);
+    }
+}
@@ -1,11 +1,16 @@
 pub type AnyError = Box<dyn std::error::Error>;
 
-pub mod arrangements {
-    pub mod binary;
-    pub mod golden;
-    pub mod linear;
-}
+pub mod arrangements;
+pub mod diff;
+pub mod export;
 pub mod git_churn;
+pub mod heatmap;
 pub mod metrics;
+pub mod progress;
+pub mod snapshot;
+#[cfg(test)]
+mod test_repo;
+pub mod timeline;
+pub mod top;
 pub mod tree;
 pub mod ui;
@@ -1,13 +1,29 @@
 use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
 
-use clap::Parser;
+use clap::builder::PossibleValuesParser;
+use clap::{Parser, ValueEnum};
 use git_version::git_version;
 use macroquad::prelude::*;
 
-use code_map::arrangements::{binary, golden, linear};
-use code_map::metrics::word_mentions::TEXT_FILE_EXTENSIONS;
+use code_map::arrangements::Arrangements;
+use code_map::diff::Diff;
+use code_map::export::{html, svg};
+use code_map::git_churn::{parse_date, ChurnOptions};
+use code_map::heatmap::Heatmap;
+use code_map::metrics::extensions::{ExtensionOptions, LANGUAGES};
+use code_map::metrics::input_file;
+use code_map::metrics::revision::BlobCache;
+use code_map::metrics::walk::WalkOptions;
 use code_map::metrics::Metrics;
+use code_map::progress::Progress;
+use code_map::snapshot::Snapshot;
+use code_map::timeline::{compute_timeline, Frame};
+use code_map::top::{ReportFormat, TopReport};
 use code_map::tree::Tree;
+use code_map::ui::progress_view::draw_progress;
+use code_map::ui::timeline_view::TimelineControls;
 use code_map::ui::Ui;
 use code_map::{metrics, AnyError};
 
@@ -25,25 +41,150 @@ pub struct Cli {
     #[arg(default_value = ".")]
     pub input_folder: PathBuf,
 
-    /// arrangement algorithm: linear, binary or golden.
+    /// arrangement algorithm
     #[arg(short, long, default_value = "golden")]
-    pub arrangement: String,
+    pub arrangement: Arrangements,
 
     /// metric to plot
     #[arg(short, long, default_value = "churn-per-file")]
     pub metric: Metrics,
 
-    // Don't filter by extension of source code files
-    // #[arg(short = 'x', long, default_value = false)]
-    // pub all_extensions: bool,
-    //
-    /// Padding in pixels between hierarchies (e.g. 4) (only for linear arrangement).
+    /// metric for the fill color of the files, matched by path with the main metric. E.g. plot
+    /// lines with `-m lines-per-file --color-metric churn-per-file` to find big files that change
+    /// often.
+    #[arg(long)]
+    pub color_metric: Option<Metrics>,
+
+    /// consider files with any extension or without extension, not only the ones of source code
+    /// or text (bytes-per-file already does)
+    #[arg(short = 'x', long, conflicts_with_all = ["include_extensions", "lang"])]
+    pub all_extensions: bool,
+
+    /// only consider files with these extensions, e.g. 'rs,toml'. Can be combined with --lang
+    #[arg(long, value_delimiter = ',')]
+    pub include_extensions: Vec<String>,
+
+    /// never consider files with these extensions, e.g. 'json,lock'
+    #[arg(long, value_delimiter = ',')]
+    pub exclude_extensions: Vec<String>,
+
+    /// only consider the files of these languages, e.g. 'rust,python'. Files without extension are
+    /// recognized by their name (e.g. Makefile) or their shebang (e.g. #!/usr/bin/env python3)
+    #[arg(long, value_delimiter = ',', value_parser = PossibleValuesParser::new(LANGUAGES.iter().map(|language| language.name)))]
+    pub lang: Vec<String>,
+
+    /// Padding in pixels between hierarchies (e.g. 4).
     #[arg(short, long, default_value = "0")]
     pub padding: f32,
 
-    /// maximum number of commits to consider (only for churn-per-file metric)
+    /// don't draw the names of files and folders inside the map.
+    #[arg(long)]
+    pub no_labels: bool,
+
+    /// only consider the files matching this glob, relative to the input folder, e.g. 'src/**'.
+    /// Can be repeated (not for churn metrics)
+    #[arg(long)]
+    pub include: Vec<String>,
+
+    /// skip the files and folders matching this glob, e.g. '*.lock' or 'tests'. Can be repeated
+    /// (not for churn metrics)
+    #[arg(long)]
+    pub exclude: Vec<String>,
+
+    /// also consider the files ignored by .gitignore, .ignore, .git/info/exclude and the global git
+    /// excludes file (not for churn metrics)
+    #[arg(long)]
+    pub no_ignore: bool,
+
+    /// maximum number of commits to consider (only for churn-per-file and refactor metrics)
     #[arg(long)]
     pub max_commits: Option<usize>,
+
+    /// only consider commits from this date onwards, e.g. 2023-12-31 (only for churn metrics)
+    #[arg(long, value_parser = parse_date)]
+    pub since: Option<i64>,
+
+    /// only consider commits up to this date, inclusive, e.g. 2023-12-31 (only for churn metrics)
+    #[arg(long, value_parser = parse_date)]
+    pub until: Option<i64>,
+
+    /// only consider commits in this revision range, e.g. v1.0..v2.0 (only for churn metrics)
+    #[arg(long)]
+    pub range: Option<String>,
+
+    /// only consider commits whose author name or email contains this text, after applying the
+    /// .mailmap (only for churn metrics)
+    #[arg(long)]
+    pub author: Option<String>,
+
+    /// ignore the churn of files that don't exist anymore in HEAD, or in the end of --range (only
+    /// for churn metrics)
+    #[arg(long)]
+    pub drop_deleted: bool,
+
+    /// don't read nor write the churn cache stored in the .git folder (only for churn metrics)
+    #[arg(long)]
+    pub no_churn_cache: bool,
+
+    /// read `path,value` or `value path` lines, or a JSON file saved with --output-file, from this
+    /// file instead of computing a metric. Use "-" to read from stdin.
+    #[arg(short, long)]
+    pub input_file: Option<PathBuf>,
+
+    /// save the computed tree as JSON in this file, to load it later with --input-file.
+    #[arg(short, long)]
+    pub output_file: Option<PathBuf>,
+
+    /// compute the metric at this git revision (e.g. a tag or a branch), reading the files from
+    /// the git objects without checking it out (only for bytes-per-file and lines-per-file)
+    #[arg(long)]
+    pub rev: Option<String>,
+
+    /// compare with the same metric at this git revision, like --diff (only for bytes-per-file and
+    /// lines-per-file)
+    #[arg(long, conflicts_with_all = ["diff", "color_metric"])]
+    pub diff_rev: Option<String>,
+
+    /// show the map at N commits evenly spread along the history of HEAD, with a slider and a play
+    /// button to go through them. Always uses the stable arrangement (only for bytes-per-file and
+    /// lines-per-file)
+    #[arg(long, conflicts_with_all = ["rev", "diff", "diff_rev", "color_metric", "input_file"])]
+    pub timeline: Option<usize>,
+
+    /// compare with a tree saved with --output-file (or any file accepted by --input-file): the
+    /// size of each file is how much it changed, and its color whether it grew or shrank.
+    #[arg(long, conflicts_with = "color_metric")]
+    pub diff: Option<PathBuf>,
+
+    /// save the map as an SVG image in this file, without opening a window.
+    #[arg(long)]
+    pub export: Option<PathBuf>,
+
+    /// save the map as an interactive HTML page in this file, without opening a window. The page
+    /// has the data embedded, so it can be shared as a single file.
+    #[arg(long)]
+    pub html: Option<PathBuf>,
+
+    /// width in pixels of the map saved with --export or --html
+    #[arg(long, default_value = "1600")]
+    pub width: u32,
+
+    /// height in pixels of the map saved with --export or --html
+    #[arg(long, default_value = "900")]
+    pub height: u32,
+
+    /// print the N biggest files and folders, without opening a window.
+    #[arg(long)]
+    pub top: Option<usize>,
+
+    /// with --top, consider the folders at this depth as files, with the size of all their
+    /// contents. The input folder is at depth 0.
+    #[arg(long, requires = "top")]
+    pub depth: Option<usize>,
+
+    /// format of the --top report
+    #[arg(long, default_value = "table", requires = "top")]
+    pub format: ReportFormat,
 }
 
 macro_rules! log_time {
@@ -63,49 +204,384 @@ macro_rules! log_time {
     }};
 }
 
-#[macroquad::main(window_conf)]
-async fn main() -> Result<(), AnyError> {
+fn main() -> Result<(), AnyError> {
     let args = Cli::parse();
-    let mut ui = compute_ui(args.clone());
-    while should_continue() {
-        if ui.should_refresh() {
-            ui = compute_ui(args.clone());
+    if args.export.is_some() || args.html.is_some() || args.top.is_some() {
+        run_headless(args)
+    } else {
+        macroquad::Window::from_config(window_conf(), async {
+            if let Err(err) = run_ui(args).await {
+                error!("Error: {:?}", err);
+            }
+        });
+        Ok(())
+    }
+}
+
+async fn run_ui(args: Cli) -> Result<(), AnyError> {
+    let mut ui: Option<Ui> = None;
+    let mut timeline: Option<Timeline> = None;
+    let mut computation = Some(Computation::start(args.clone()));
+    while !is_quit_pressed() {
+        if is_key_pressed(KeyCode::Escape) {
+            if let Some(cancelled) = computation.take() {
+                cancelled.progress.cancel();
+                info!("Cancelled: {}", cancelled.description);
+                if ui.is_none() {
+                    // there's nothing else to show
+                    break;
+                }
+            } else {
+                break;
+            }
+        }
+        if let Some(result) = computation.as_ref().and_then(Computation::try_result) {
+            computation = None;
+            match result {
+                Ok(mut computed) => {
+                    let frames = std::mem::take(&mut computed.frames);
+                    timeline = (!frames.is_empty()).then(|| Timeline {
+                        current: frames.len() - 1,
+                        frames,
+                        controls: TimelineControls::default(),
+                    });
+                    let mut new_ui = create_ui(computed, &args, ui.as_ref());
+                    if let Some(previous) = &ui {
+                        new_ui.zoom_like(previous);
+                    }
+                    ui = Some(new_ui);
+                }
+                Err(e) if ui.is_none() => return Err(e.into()),
+                Err(e) => error!("Could not refresh the metrics: {}", e),
+            }
+        }
+        if let Some(ui) = &mut ui {
+            if ui.should_refresh() && computation.is_none() {
+                if args
+                    .input_file
+                    .as_ref()
+                    .is_some_and(|file| input_file::is_stdin(file))
+                {
+                    warn!("Can't refresh metrics that were read from stdin");
+                } else {
+                    computation = Some(Computation::start(args.clone()));
+                }
+            }
+            // the previous map stays interactive while a refresh is being computed
+            ui.draw();
+            if let Some(timeline) = &mut timeline {
+                let shown = timeline.controls.draw(
+                    timeline.current,
+                    timeline.frames.len(),
+                    &timeline.frames[timeline.current].label(),
+                    ui.map_rect,
+                    ui.font_size(),
+                );
+                if let Some(shown) = shown {
+                    timeline.current = shown;
+                    ui.replace_tree(timeline.frames[shown].tree.clone());
+                }
+            }
+        } else {
+            clear_background(LIGHTGRAY);
+        }
+        if let Some(computation) = &computation {
+            draw_progress(&computation.progress, &computation.description);
         }
-        ui.draw();
         next_frame().await
     }
     Ok(())
 }
 
-fn compute_ui(args: Cli) -> Ui {
-    let all_extensions = true;
+/// A tree being computed in another thread, so that the window doesn't freeze meanwhile.
+struct Computation {
+    description: String,
+    progress: Arc<Progress>,
+    result: Receiver<Result<Computed, String>>,
+}
+
+/// The result of a `Computation`.
+struct Computed {
+    tree: Tree,
+    units: String,
+    heatmap: Option<Heatmap>,
+    /// the trees of --timeline, from the oldest to the newest, which is also `tree`
+    frames: Vec<Frame>,
+}
+
+/// The frames of --timeline, and which one is shown.
+struct Timeline {
+    frames: Vec<Frame>,
+    current: usize,
+    controls: TimelineControls,
+}
+
+impl Computation {
+    fn start(args: Cli) -> Self {
+        let description = if let Some(input_file) = &args.input_file {
+            format!("Reading {}", input_file.to_string_lossy())
+        } else {
+            let metric = args.metric.to_possible_value().unwrap();
+            format!("Computing {}", metric.get_name())
+        };
+        let progress = Arc::new(Progress::new());
+        let (sender, result) = mpsc::channel();
+        let thread_progress = progress.clone();
+        std::thread::spawn(move || {
+            let tree = compute_tree(args, thread_progress).map_err(|e| e.to_string());
+            // if the computation was cancelled nobody is listening anymore, which is fine
+            let _ = sender.send(tree);
+        });
+        Self {
+            description,
+            progress,
+            result,
+        }
+    }
+
+    fn try_result(&self) -> Option<Result<Computed, String>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(Err("the thread computing the metrics panicked".to_string()))
+            }
+        }
+    }
+}
+
+fn compute_tree(args: Cli, progress: Arc<Progress>) -> Result<Computed, AnyError> {
     let Cli {
         input_folder,
-        padding,
-        arrangement,
+        padding: _,
+        arrangement: _,
+        no_labels: _,
         metric,
-        // all_extensions,
+        color_metric,
+        all_extensions,
+        include_extensions,
+        exclude_extensions,
+        lang,
+        include,
+        exclude,
+        no_ignore,
         max_commits,
+        since,
+        until,
+        range,
+        author,
+        drop_deleted,
+        no_churn_cache,
+        input_file,
+        output_file,
+        rev,
+        diff_rev,
+        timeline,
+        diff,
+        export: _,
+        html: _,
+        width: _,
+        height: _,
+        top: _,
+        depth: _,
+        format: _,
     } = args;
-    let (tree, units) = log_time!(
-        compute_metrics(&input_folder, &metric, all_extensions, max_commits),
-        format!("computing metrics {:?}", metric)
-    );
+    let churn_options = ChurnOptions {
+        max_commits,
+        since,
+        until,
+        range,
+        author,
+        drop_deleted,
+        count_lines: false,
+        use_cache: !no_churn_cache,
+        progress,
+    };
+    let extensions = ExtensionOptions {
+        all_extensions,
+        include_extensions,
+        exclude_extensions,
+        languages: lang,
+    };
+    let walk_options = WalkOptions {
+        include,
+        exclude,
+        no_ignore,
+    };
+    let mut frames = Vec::new();
+    let (mut tree, mut units) = if let Some(input_file) = input_file {
+        let (tree, units) = log_time!(
+            input_file::tree_from_input_file(&input_file, input_folder.clone())?,
+            format!("reading {:?}", input_file)
+        );
+        (tree, units.unwrap_or_else(|| "units".to_string()))
+    } else if let Some(count) = timeline {
+        let units;
+        (frames, units) = log_time!(
+            compute_frames(
+                &input_folder,
+                &metric,
+                count,
+                &extensions,
+                &walk_options,
+                &churn_options.progress
+            )?,
+            format!("computing the timeline of {:?}", metric)
+        );
+        let last = frames
+            .last()
+            .ok_or("there are no commits with files to show")?;
+        (last.tree.clone(), units.to_string())
+    } else {
+        let (tree, units) = log_time!(
+            compute_metrics(
+                &input_folder,
+                &metric,
+                &extensions,
+                &walk_options,
+                &churn_options,
+                rev.as_deref()
+            )?,
+            format!("computing metrics {:?}", metric)
+        );
+        (tree, units.to_string())
+    };
+    let mut heatmap = if let Some(color_metric) = color_metric {
+        let (color_tree, color_units) = log_time!(
+            compute_metrics(
+                &input_folder,
+                &color_metric,
+                &extensions,
+                &walk_options,
+                &churn_options,
+                rev.as_deref()
+            )?,
+            format!("computing color metric {:?}", color_metric)
+        );
+        Some(Heatmap::new(&tree, &color_tree, color_units))
+    } else {
+        None
+    };
+    if let Some(output_file) = output_file {
+        let snapshot = Snapshot::new(tree, &units);
+        log_time!(
+            snapshot.save(&output_file)?,
+            format!("saving {:?}", output_file)
+        );
+        tree = snapshot.tree;
+    }
+    let before = if let Some(diff) = diff {
+        let (before, _) = log_time!(
+            input_file::tree_from_input_file(&diff, input_folder)?,
+            format!("reading {:?}", diff)
+        );
+        Some(before)
+    } else if let Some(diff_rev) = diff_rev {
+        let (before, _) = log_time!(
+            compute_metrics(
+                &input_folder,
+                &metric,
+                &extensions,
+                &walk_options,
+                &churn_options,
+                Some(&diff_rev)
+            )?,
+            format!("computing metrics {:?} at {}", metric, diff_rev)
+        );
+        Some(before)
+    } else {
+        None
+    };
+    if let Some(before) = before {
+        let diff = Diff::new(&before, &tree, &units)?;
+        tree = diff.tree;
+        units = diff.units;
+        heatmap = Some(diff.heatmap);
+    }
+    Ok(Computed {
+        tree,
+        units,
+        heatmap,
+        frames,
+    })
+}
 
-    let mut ui = Ui::new(tree, units, arrange, arrangement.clone(), padding);
+/// Computes the tree without opening a window, prints the --top report and saves the map in the
+/// files chosen with --export and --html.
+fn run_headless(args: Cli) -> Result<(), AnyError> {
+    let (width, height) = (args.width as f32, args.height as f32);
+    let arrangement = args.arrangement.new_arrangement(args.padding);
+    let (svg_path, html_path) = (args.export.clone(), args.html.clone());
+    let (top, depth, format) = (args.top, args.depth, args.format);
+    let Computed {
+        mut tree,
+        units,
+        heatmap,
+        ..
+    } = compute_tree(args, Arc::new(Progress::new()))?;
+    if let Some(top) = top {
+        print!(
+            "{}",
+            TopReport::new(&tree, &units, top, depth).format(format)?
+        );
+    }
+    if svg_path.is_none() && html_path.is_none() {
+        return Ok(());
+    }
     log_time!(
-        arrange(padding, arrangement.clone(), &mut ui.tree, ui.map_rect),
+        arrangement.arrange(&mut tree, Rect::new(0.0, 0.0, width, height)),
         "arrangement"
     );
+    if let Some(path) = svg_path {
+        svg::save_svg(&path, &tree, &units, heatmap.as_ref(), width, height)?;
+        info!("Saved the map to {:?}", path);
+    }
+    if let Some(path) = html_path {
+        html::save_html(&path, &tree, &units, heatmap.as_ref(), width, height)?;
+        info!("Saved the report to {:?}", path);
+    }
+    Ok(())
+}
+
+fn create_ui(computed: Computed, args: &Cli, previous: Option<&Ui>) -> Ui {
+    let Computed {
+        mut tree,
+        units,
+        heatmap,
+        frames: _,
+    } = computed;
+    let Cli {
+        padding,
+        arrangement,
+        no_labels,
+        timeline,
+        ..
+    } = args;
+    // so that the rects don't jump around when going through the timeline
+    let arrangement = if timeline.is_some() {
+        Arrangements::Stable
+    } else {
+        *arrangement
+    };
+    let arrangement = arrangement.new_arrangement(*padding);
+    if let Some(previous) = previous {
+        if arrangement.uses_previous_rects() {
+            tree.copy_rects_from(&previous.tree);
+        }
+    }
+    let mut ui = Ui::new(tree, &units, arrangement, !no_labels);
+    if let Some(heatmap) = heatmap {
+        ui.set_heatmap(heatmap);
+    }
+    log_time!(ui.arrange(), "arrangement");
     log_time!(log_counts(&ui.tree));
     ui
 }
-fn should_continue() -> bool {
-    let ctrl_q_pressed = is_key_pressed(KeyCode::Q)
-        && (is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl));
-    let escape_pressed = is_key_down(KeyCode::Escape);
-    let should_quit = ctrl_q_pressed || escape_pressed;
-    !should_quit
+
+/// Escape is not included because it's also used to cancel the computation of the metrics.
+fn is_quit_pressed() -> bool {
+    is_key_pressed(KeyCode::Q)
+        && (is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl))
 }
 
 fn window_conf() -> Conf {
@@ -121,56 +597,177 @@ fn window_conf() -> Conf {
 fn compute_metrics(
     input_folder: &PathBuf,
     metric: &Metrics,
-    all_extensions: bool,
-    max_commits: Option<usize>,
-) -> (Tree, &'static str) {
+    extensions: &ExtensionOptions,
+    walk_options: &WalkOptions,
+    churn_options: &ChurnOptions,
+    rev: Option<&str>,
+) -> Result<(Tree, &'static str), AnyError> {
+    if let Some(rev) = rev {
+        return compute_metrics_at(
+            input_folder,
+            metric,
+            rev,
+            extensions,
+            walk_options,
+            &churn_options.progress,
+        );
+    }
     let (tree, units) = match metric {
+        Metrics::AuthorsPerFile => (
+            metrics::churn_per_file::authors_per_file(input_folder.clone(), churn_options)?,
+            "authors",
+        ),
         Metrics::BytesPerFile => (
-            if all_extensions {
-                metrics::bytes_per_file::bytes_per_file(&input_folder).unwrap()
-            } else {
-                metrics::bytes_per_file::bytes_per_file_with_extension(
-                    &input_folder,
-                    TEXT_FILE_EXTENSIONS,
-                )
-                .unwrap()
-                .unwrap()
-            },
+            metrics::bytes_per_file::bytes_per_file(
+                &input_folder,
+                extensions,
+                walk_options,
+                &churn_options.progress,
+            )?,
             "bytes",
         ),
         Metrics::WordMentions => (
-            metrics::word_mentions::word_mentions(&input_folder).unwrap(),
+            metrics::word_mentions::word_mentions(
+                &input_folder,
+                extensions,
+                walk_options,
+                &churn_options.progress,
+            )?,
             "mentions",
         ),
+        Metrics::LinesAddedPerFile => (
+            metrics::churn_per_file::lines_added_per_file(input_folder.clone(), churn_options)?,
+            "lines added",
+        ),
+        Metrics::LinesChangedPerFile => (
+            metrics::churn_per_file::lines_changed_per_file(input_folder.clone(), churn_options)?,
+            "lines changed (added + deleted)",
+        ),
+        Metrics::LinesDeletedPerFile => (
+            metrics::churn_per_file::lines_deleted_per_file(input_folder.clone(), churn_options)?,
+            "lines deleted",
+        ),
         Metrics::LinesPerFile => (
-            metrics::lines::lines_per_file(&input_folder)
-                .unwrap()
-                .unwrap(),
+            metrics::lines::lines_per_file(
+                &input_folder,
+                extensions,
+                walk_options,
+                &churn_options.progress,
+            )?
+            .ok_or("no source code files to count lines")?,
             "lines",
         ),
         Metrics::ChurnPerFile => (
-            metrics::churn_per_file::git_churn_per_file(input_folder.clone(), max_commits).unwrap(),
+            metrics::churn_per_file::git_churn_per_file(input_folder.clone(), churn_options)?,
             "modifications (commits per file)",
         ),
+        Metrics::OwnershipPerFile => (
+            metrics::churn_per_file::ownership_per_file(input_folder.clone(), churn_options)?,
+            "% of commits by the top author",
+        ),
+        Metrics::Refactor => (
+            metrics::refactor::refactor_per_file(
+                input_folder.clone(),
+                extensions,
+                walk_options,
+                churn_options,
+            )?,
+            "commits * lines",
+        ),
     };
-    (tree, units)
+    Ok((tree, units))
 }
 
-fn arrange(padding: f32, arrangement: String, mut treemap: &mut Tree, available: Rect) {
-    if arrangement == "linear" {
-        linear::arrange(&mut treemap, available, padding);
-    } else if arrangement == "binary" {
-        binary::arrange(&mut treemap, available);
-    } else if arrangement == "golden" {
-        golden::arrange(&mut treemap, available);
-    } else {
-        panic!(
-            "Unknown arrangement algorithm: {}. valid ones are: linear, binary",
-            arrangement
-        );
+/// Computes the metric at `count` commits of the history of HEAD. Only the metrics that can be
+/// computed at another revision are supported.
+fn compute_frames(
+    input_folder: &PathBuf,
+    metric: &Metrics,
+    count: usize,
+    extensions: &ExtensionOptions,
+    walk_options: &WalkOptions,
+    progress: &Progress,
+) -> Result<(Vec<Frame>, &'static str), AnyError> {
+    // most files don't change between frames, so each version of a file is only measured once
+    let mut cache = BlobCache::new();
+    match metric {
+        Metrics::BytesPerFile => Ok((
+            compute_timeline(input_folder, count, progress, |id| {
+                let tree = metrics::revision::bytes_per_file_at(
+                    input_folder,
+                    id,
+                    extensions,
+                    walk_options,
+                    &mut cache,
+                    progress,
+                )?;
+                Ok(Some(tree).filter(|tree| tree.size() > 0))
+            })?,
+            "bytes",
+        )),
+        Metrics::LinesPerFile => Ok((
+            compute_timeline(input_folder, count, progress, |id| {
+                metrics::revision::lines_per_file_at(
+                    input_folder,
+                    id,
+                    extensions,
+                    walk_options,
+                    &mut cache,
+                    progress,
+                )
+            })?,
+            "lines",
+        )),
+        _ => Err(unsupported_at_revision(metric)),
+    }
+}
+
+/// Only the metrics that read the content of the files can be computed at another revision.
+fn compute_metrics_at(
+    input_folder: &PathBuf,
+    metric: &Metrics,
+    rev: &str,
+    extensions: &ExtensionOptions,
+    walk_options: &WalkOptions,
+    progress: &Progress,
+) -> Result<(Tree, &'static str), AnyError> {
+    match metric {
+        Metrics::BytesPerFile => Ok((
+            metrics::revision::bytes_per_file_at(
+                input_folder,
+                rev,
+                extensions,
+                walk_options,
+                &mut BlobCache::new(),
+                progress,
+            )?,
+            "bytes",
+        )),
+        Metrics::LinesPerFile => Ok((
+            metrics::revision::lines_per_file_at(
+                input_folder,
+                rev,
+                extensions,
+                walk_options,
+                &mut BlobCache::new(),
+                progress,
+            )?
+            .ok_or("no source code files to count lines")?,
+            "lines",
+        )),
+        _ => Err(unsupported_at_revision(metric)),
     }
 }
 
+fn unsupported_at_revision(metric: &Metrics) -> AnyError {
+    let metric = metric.to_possible_value().unwrap();
+    format!(
+        "a git revision can only be used with bytes-per-file and lines-per-file, not with {}",
+        metric.get_name()
+    )
+    .into()
+}
+
 fn log_counts(treemap: &Tree) {
     let counts = treemap.count();
     info!(
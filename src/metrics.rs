@@ -3,21 +3,38 @@ use clap::ValueEnum;
 
 pub mod bytes_per_file;
 pub mod churn_per_file;
+pub mod extensions;
+pub mod input_file;
 pub mod lines;
+pub mod refactor;
+pub mod revision;
+pub mod walk;
 pub mod word_mentions;
 
 #[derive(Copy, Clone, Debug)]
 pub enum Metrics {
+    AuthorsPerFile,
     BytesPerFile,
     ChurnPerFile,
+    LinesAddedPerFile,
+    LinesChangedPerFile,
+    LinesDeletedPerFile,
     LinesPerFile,
+    OwnershipPerFile,
+    Refactor,
     WordMentions,
 }
 
-const METRICS: [Metrics; 4] = [
+const METRICS: [Metrics; 10] = [
+    Metrics::AuthorsPerFile,
     Metrics::BytesPerFile,
     Metrics::ChurnPerFile,
+    Metrics::LinesAddedPerFile,
+    Metrics::LinesChangedPerFile,
+    Metrics::LinesDeletedPerFile,
     Metrics::LinesPerFile,
+    Metrics::OwnershipPerFile,
+    Metrics::Refactor,
     Metrics::WordMentions,
 ];
 
@@ -28,10 +45,59 @@ impl ValueEnum for Metrics {
 
     fn to_possible_value(&self) -> Option<PossibleValue> {
         match self {
+            Metrics::AuthorsPerFile => Some(PossibleValue::new("authors-per-file").alias("a")),
             Metrics::BytesPerFile => Some(PossibleValue::new("bytes-per-file").alias("b")),
             Metrics::ChurnPerFile => Some(PossibleValue::new("churn-per-file").alias("c")),
+            Metrics::LinesAddedPerFile => {
+                Some(PossibleValue::new("lines-added-per-file").alias("la"))
+            }
+            Metrics::LinesChangedPerFile => {
+                Some(PossibleValue::new("lines-changed-per-file").alias("lc"))
+            }
+            Metrics::LinesDeletedPerFile => {
+                Some(PossibleValue::new("lines-deleted-per-file").alias("ld"))
+            }
             Metrics::LinesPerFile => Some(PossibleValue::new("lines-per-file").alias("l")),
+            Metrics::OwnershipPerFile => Some(PossibleValue::new("ownership-per-file").alias("o")),
+            Metrics::Refactor => Some(PossibleValue::new("refactor").alias("r")),
             Metrics::WordMentions => Some(PossibleValue::new("word-mentions").alias("w")),
         }
     }
 }
+
+/// Formats a value with its units, separating the thousands with spaces, e.g. "12 345 lines".
+pub fn format_units(value: i64, units: &str) -> String {
+    format!("{} {}", format_number(value), units)
+}
+
+/// Separates the thousands with spaces, e.g. "-12 345".
+pub fn format_number(value: i64) -> String {
+    let separator = " ";
+    let space_separated_number = value
+        .unsigned_abs()
+        .to_string()
+        .as_bytes()
+        .rchunks(3)
+        .rev()
+        .collect::<Vec<&[u8]>>()
+        .join(separator.as_bytes());
+    let sign = if value < 0 { "-" } else { "" };
+    format!(
+        "{}{}",
+        sign,
+        String::from_utf8(space_separated_number).unwrap()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(1234567, "lines"), "1 234 567 lines");
+        assert_eq!(format_units(123, "lines"), "123 lines");
+        assert_eq!(format_units(-123, "lines"), "-123 lines");
+        assert_eq!(format_number(-1234), "-1 234");
+    }
+}
@@ -1,29 +1,85 @@
 use std::path::PathBuf;
 
-use crate::git_churn::{git_churn, FileChurn};
+use crate::git_churn::{git_churn, ChurnOptions, FileChurn};
 use crate::tree::Tree;
 use crate::AnyError;
 
-pub fn git_churn_per_file(folder: PathBuf, max_commits: Option<usize>) -> Result<Tree, AnyError> {
-    let file_churns = git_churn(folder.clone(), max_commits)?;
+pub fn git_churn_per_file(folder: PathBuf, options: &ChurnOptions) -> Result<Tree, AnyError> {
+    let file_churns = git_churn(folder.clone(), options)?;
     let tree = file_churns_to_tree(folder, file_churns);
     tree
 }
 
+/// Number of distinct authors that changed each file
+pub fn authors_per_file(folder: PathBuf, options: &ChurnOptions) -> Result<Tree, AnyError> {
+    let file_churns = git_churn(folder.clone(), options)?;
+    file_churns_to_tree_by(folder, file_churns, |file_churn| {
+        file_churn.distinct_authors() as i64
+    })
+}
+
+/// Percentage of the changes of each file done by its top author
+pub fn ownership_per_file(folder: PathBuf, options: &ChurnOptions) -> Result<Tree, AnyError> {
+    let file_churns = git_churn(folder.clone(), options)?;
+    file_churns_to_tree_by(folder, file_churns, |file_churn| {
+        file_churn.top_author_share() as i64
+    })
+}
+
+/// Sum of lines added and deleted in each file
+pub fn lines_changed_per_file(folder: PathBuf, options: &ChurnOptions) -> Result<Tree, AnyError> {
+    let file_churns = git_churn_with_lines(folder.clone(), options)?;
+    file_churns_to_tree_by(folder, file_churns, |file_churn| {
+        file_churn.lines_added + file_churn.lines_deleted
+    })
+}
+
+pub fn lines_added_per_file(folder: PathBuf, options: &ChurnOptions) -> Result<Tree, AnyError> {
+    let file_churns = git_churn_with_lines(folder.clone(), options)?;
+    file_churns_to_tree_by(folder, file_churns, |file_churn| file_churn.lines_added)
+}
+
+pub fn lines_deleted_per_file(folder: PathBuf, options: &ChurnOptions) -> Result<Tree, AnyError> {
+    let file_churns = git_churn_with_lines(folder.clone(), options)?;
+    file_churns_to_tree_by(folder, file_churns, |file_churn| file_churn.lines_deleted)
+}
+
+fn git_churn_with_lines(
+    folder: PathBuf,
+    options: &ChurnOptions,
+) -> Result<Vec<FileChurn>, AnyError> {
+    let options = ChurnOptions {
+        count_lines: true,
+        ..options.clone()
+    };
+    git_churn(folder, &options)
+}
+
 fn file_churns_to_tree(folder: PathBuf, file_churns: Vec<FileChurn>) -> Result<Tree, AnyError> {
-    let nodes_flat_list = file_churns_to_nodes(file_churns);
+    file_churns_to_tree_by(folder, file_churns, |file_churn| file_churn.count as i64)
+}
+
+fn file_churns_to_tree_by(
+    folder: PathBuf,
+    file_churns: Vec<FileChurn>,
+    value: fn(&FileChurn) -> i64,
+) -> Result<Tree, AnyError> {
+    let nodes_flat_list = file_churns_to_nodes(file_churns, value);
     let tree = nodes_flat_list_to_tree(nodes_flat_list, folder);
     tree
 }
 
-fn file_churns_to_nodes(file_churns: Vec<FileChurn>) -> Vec<Tree> {
+fn file_churns_to_nodes(file_churns: Vec<FileChurn>, value: fn(&FileChurn) -> i64) -> Vec<Tree> {
     file_churns
         .into_iter()
-        .map(|FileChurn { path, count }| Tree::new_from_size(path, count as i64))
+        .map(|file_churn| {
+            let size = value(&file_churn);
+            Tree::new_from_size(file_churn.path, size)
+        })
         .collect::<Vec<Tree>>()
 }
 
-fn nodes_flat_list_to_tree(nodes: Vec<Tree>, folder: PathBuf) -> Result<Tree, AnyError> {
+pub(crate) fn nodes_flat_list_to_tree(nodes: Vec<Tree>, folder: PathBuf) -> Result<Tree, AnyError> {
     let mut top_level_folder = folder.to_string_lossy().to_string();
     if top_level_folder.is_empty() {
         return Err("folder should not be empty".into());
@@ -150,10 +206,10 @@ mod tests {
     fn test_churn_tree_creation() {
         #[rustfmt::skip]
         let file_churns = vec![
-            FileChurn { path: "src/arrangements/binary.rs".into(), count: 1 },
-            FileChurn { path: "src/metrics/word_mentions.rs".into(), count: 2 },
-            FileChurn { path: "src/main.rs".into(), count: 3 },
-            FileChurn { path: "src/metrics/bytes_per_file.rs".into(), count: 4 },
+            FileChurn { path: "src/arrangements/binary.rs".into(), count: 1, ..Default::default() },
+            FileChurn { path: "src/metrics/word_mentions.rs".into(), count: 2, ..Default::default() },
+            FileChurn { path: "src/main.rs".into(), count: 3, ..Default::default() },
+            FileChurn { path: "src/metrics/bytes_per_file.rs".into(), count: 4, ..Default::default() },
         ];
 
         let tree = file_churns_to_tree(".".into(), file_churns).unwrap();
@@ -178,8 +234,8 @@ mod tests {
     fn test_churn_tree_creation_basic() {
         #[rustfmt::skip]
         let file_churns = vec![
-            FileChurn { path: "main.rs".into(), count: 1 },
-            FileChurn { path: "lib.rs".into(), count: 2 },
+            FileChurn { path: "main.rs".into(), count: 1, ..Default::default() },
+            FileChurn { path: "lib.rs".into(), count: 2, ..Default::default() },
         ];
 
         let tree = file_churns_to_tree(".".into(), file_churns).unwrap();
@@ -196,8 +252,8 @@ mod tests {
     fn test_churn_tree_creation_one_level_deep() {
         #[rustfmt::skip]
             let file_churns = vec![
-            FileChurn { path: "src/main.rs".into(), count: 1 },
-            FileChurn { path: "src/lib.rs".into(), count: 2 },
+            FileChurn { path: "src/main.rs".into(), count: 1, ..Default::default() },
+            FileChurn { path: "src/lib.rs".into(), count: 2, ..Default::default() },
         ];
 
         let tree = file_churns_to_tree(".".into(), file_churns).unwrap();
@@ -216,8 +272,8 @@ mod tests {
     fn test_churn_tree_creation_complex_top_level() {
         #[rustfmt::skip]
             let file_churns = vec![
-            FileChurn { path: "src/main.rs".into(), count: 1 },
-            FileChurn { path: "src/lib.rs".into(), count: 2 },
+            FileChurn { path: "src/main.rs".into(), count: 1, ..Default::default() },
+            FileChurn { path: "src/lib.rs".into(), count: 2, ..Default::default() },
         ];
 
         let tree = file_churns_to_tree("./src/../".into(), file_churns).unwrap();
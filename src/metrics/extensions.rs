@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::AnyError;
+
+#[rustfmt::skip]
+pub const TEXT_FILE_EXTENSIONS: &[&str] = &[
+    "adoc", "bash", "c", "cc", "cjs", "clj", "cmake", "cpp", "cs", "css", "cxx", "dart", "ex",
+    "exs", "go", "gradle", "h", "hpp", "hs", "html", "ini", "java", "js", "json", "jsx", "kt",
+    "kts", "lua", "m", "mjs", "mm", "md", "pbproj", "php", "pl", "proto", "py", "pyi", "rb", "rs",
+    "rst", "sc", "scala", "scss", "sh", "sql", "svelte", "swift", "toml", "ts", "tsx", "txt",
+    "vue", "xml", "yaml", "yml", "zig", "zsh",
+];
+
+#[rustfmt::skip]
+pub const CODE_FILE_EXTENSIONS: &[&str] = &[
+    "bash", "c", "cc", "cjs", "clj", "cmake", "cpp", "cs", "css", "cxx", "dart", "ex", "exs",
+    "go", "gradle", "h", "hpp", "hs", "html", "java", "js", "json", "jsx", "kt", "kts", "lua",
+    "m", "mjs", "mm", "md", "pbproj", "php", "pl", "proto", "py", "pyi", "rb", "rs", "sc",
+    "scala", "scss", "sh", "sql", "svelte", "swift", "toml", "ts", "tsx", "vue", "xml", "yaml",
+    "yml", "zig", "zsh",
+];
+
+/// A group of extensions that can be chosen by name with `--lang`.
+pub struct Language {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    /// names of files without extension, e.g. "Makefile"
+    pub file_names: &'static [&'static str],
+    /// programs in the shebang of scripts without extension, e.g. "python" for
+    /// `#!/usr/bin/env python3`
+    pub interpreters: &'static [&'static str],
+}
+
+#[rustfmt::skip]
+pub const LANGUAGES: &[Language] = &[
+    Language { name: "c", extensions: &["c", "h"], file_names: &[], interpreters: &[] },
+    Language { name: "cpp", extensions: &["cc", "cpp", "cxx", "h", "hpp"], file_names: &[], interpreters: &[] },
+    Language { name: "csharp", extensions: &["cs"], file_names: &[], interpreters: &[] },
+    Language { name: "go", extensions: &["go"], file_names: &[], interpreters: &[] },
+    Language { name: "java", extensions: &["java", "gradle"], file_names: &[], interpreters: &[] },
+    Language { name: "javascript", extensions: &["cjs", "js", "jsx", "mjs"], file_names: &[], interpreters: &["node"] },
+    Language { name: "kotlin", extensions: &["kt", "kts"], file_names: &[], interpreters: &[] },
+    Language { name: "make", extensions: &["cmake", "mk"], file_names: &["GNUmakefile", "Makefile", "makefile"], interpreters: &["make"] },
+    Language { name: "markdown", extensions: &["md"], file_names: &[], interpreters: &[] },
+    Language { name: "objc", extensions: &["h", "m", "mm"], file_names: &[], interpreters: &[] },
+    Language { name: "perl", extensions: &["pl", "pm"], file_names: &[], interpreters: &["perl"] },
+    Language { name: "php", extensions: &["php"], file_names: &[], interpreters: &["php"] },
+    Language { name: "python", extensions: &["py", "pyi"], file_names: &["SConstruct"], interpreters: &["python"] },
+    Language { name: "ruby", extensions: &["rb"], file_names: &["Gemfile", "Rakefile"], interpreters: &["ruby"] },
+    Language { name: "rust", extensions: &["rs"], file_names: &[], interpreters: &[] },
+    Language { name: "scala", extensions: &["sc", "scala"], file_names: &[], interpreters: &["scala"] },
+    Language { name: "shell", extensions: &["bash", "sh", "zsh"], file_names: &[], interpreters: &["bash", "sh", "zsh"] },
+    Language { name: "sql", extensions: &["sql"], file_names: &[], interpreters: &[] },
+    Language { name: "swift", extensions: &["swift"], file_names: &[], interpreters: &["swift"] },
+    Language { name: "typescript", extensions: &["ts", "tsx"], file_names: &[], interpreters: &["deno", "ts-node"] },
+    Language { name: "web", extensions: &["css", "html", "scss", "svelte", "vue"], file_names: &[], interpreters: &[] },
+    Language { name: "config", extensions: &["ini", "json", "toml", "xml", "yaml", "yml"], file_names: &["Dockerfile", "Jenkinsfile"], interpreters: &[] },
+];
+
+/// How many bytes are read to guess the kind of a file, like git does to detect binary files.
+const SNIFF_LENGTH: usize = 8000;
+
+/// The extensions chosen in the command line. Each metric has its own default extensions, e.g.
+/// source code for lines-per-file, that these options replace or reduce.
+#[derive(Clone, Debug, Default)]
+pub struct ExtensionOptions {
+    /// consider files with any extension, or without extension
+    pub all_extensions: bool,
+    /// only consider these extensions (without the dot), together with the ones of `languages`
+    pub include_extensions: Vec<String>,
+    /// never consider these extensions (without the dot)
+    pub exclude_extensions: Vec<String>,
+    /// only consider the files of these languages, by the names in `LANGUAGES`
+    pub languages: Vec<String>,
+}
+
+/// Which files without extension are considered, by the language guessed from their name or
+/// shebang.
+#[derive(Debug)]
+enum Extensionless {
+    All,
+    AnyLanguage,
+    Languages(Vec<&'static str>),
+}
+
+/// Decides which files a metric considers, by their extension.
+#[derive(Debug)]
+pub struct ExtensionFilter {
+    /// None means any extension
+    extensions: Option<HashSet<String>>,
+    excluded: HashSet<String>,
+    extensionless: Extensionless,
+}
+
+impl ExtensionFilter {
+    /// `defaults` are the extensions considered by the metric when there are no options, or None
+    /// to consider all files.
+    pub fn new(defaults: Option<&[&str]>, options: &ExtensionOptions) -> Result<Self, AnyError> {
+        let languages = options
+            .languages
+            .iter()
+            .map(|name| find_language(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (extensions, extensionless) = if options.all_extensions {
+            (None, Extensionless::All)
+        } else if !options.include_extensions.is_empty() || !languages.is_empty() {
+            let mut extensions = normalize(&options.include_extensions);
+            for language in &languages {
+                extensions.extend(language.extensions.iter().map(|ext| ext.to_string()));
+            }
+            let names = languages.iter().map(|language| language.name).collect();
+            (Some(extensions), Extensionless::Languages(names))
+        } else if let Some(defaults) = defaults {
+            let extensions = defaults.iter().map(|ext| ext.to_string()).collect();
+            (Some(extensions), Extensionless::AnyLanguage)
+        } else {
+            (None, Extensionless::All)
+        };
+        Ok(Self {
+            extensions,
+            excluded: normalize(&options.exclude_extensions),
+            extensionless,
+        })
+    }
+
+    /// Files without extension are read to guess their language if needed.
+    pub fn accepts(&self, file: &Path) -> bool {
+        self.accepts_with(file, || read_start(file))
+    }
+
+    /// Like `accepts`, but `start` gives the first bytes of the file, e.g. from a git blob.
+    pub fn accepts_with(&self, file: &Path, start: impl FnOnce() -> Option<Vec<u8>>) -> bool {
+        match file.extension() {
+            Some(extension) => {
+                let extension = extension.to_string_lossy();
+                !self.excluded.contains(extension.as_ref())
+                    && self
+                        .extensions
+                        .as_ref()
+                        .map_or(true, |extensions| extensions.contains(extension.as_ref()))
+            }
+            None => match &self.extensionless {
+                Extensionless::All => true,
+                Extensionless::AnyLanguage => guess_language(file, start).is_some(),
+                Extensionless::Languages(names) => {
+                    guess_language(file, start).is_some_and(|name| names.contains(&name))
+                }
+            },
+        }
+    }
+}
+
+fn find_language(name: &str) -> Result<&'static Language, AnyError> {
+    LANGUAGES
+        .iter()
+        .find(|language| language.name == name)
+        .ok_or_else(|| {
+            let names = LANGUAGES
+                .iter()
+                .map(|language| language.name)
+                .collect::<Vec<_>>();
+            format!(
+                "unknown language {:?}, use one of {}",
+                name,
+                names.join(", ")
+            )
+            .into()
+        })
+}
+
+/// Accepts extensions written as "rs" or ".rs".
+fn normalize(extensions: &[String]) -> HashSet<String> {
+    extensions
+        .iter()
+        .map(|extension| extension.trim_start_matches('.').to_string())
+        .collect()
+}
+
+/// Guesses the language of a file without extension by its name, e.g. "Makefile", or by the
+/// program in its shebang, e.g. `#!/usr/bin/env python3`.
+fn guess_language(file: &Path, start: impl FnOnce() -> Option<Vec<u8>>) -> Option<&'static str> {
+    let file_name = file.file_name()?.to_string_lossy();
+    if let Some(language) = LANGUAGES
+        .iter()
+        .find(|language| language.file_names.contains(&file_name.as_ref()))
+    {
+        return Some(language.name);
+    }
+    let interpreter = shebang_interpreter(&start()?)?;
+    LANGUAGES
+        .iter()
+        .find(|language| language.interpreters.contains(&interpreter.as_str()))
+        .map(|language| language.name)
+}
+
+/// "python" for `#!/usr/bin/env python3.11 -u` or `#!/usr/bin/python3`.
+fn shebang_interpreter(start: &[u8]) -> Option<String> {
+    let line = start
+        .strip_prefix(b"#!")?
+        .split(|byte| *byte == b'\n')
+        .next()?;
+    let line = String::from_utf8_lossy(line);
+    let mut words = line.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-'))?;
+    }
+    Some(
+        program
+            .trim_end_matches(|c: char| c.is_ascii_digit() || c == '.')
+            .to_string(),
+    )
+}
+
+/// Files with a null byte at the start are considered binary, like git does.
+pub fn is_binary(content: &[u8]) -> bool {
+    content[..content.len().min(SNIFF_LENGTH)].contains(&0)
+}
+
+fn read_start(file: &Path) -> Option<Vec<u8>> {
+    let mut start = Vec::new();
+    File::open(file)
+        .ok()?
+        .take(SNIFF_LENGTH as u64)
+        .read_to_end(&mut start)
+        .ok()?;
+    Some(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accepts(filter: &ExtensionFilter, file: &str, content: &str) -> bool {
+        filter.accepts_with(Path::new(file), || Some(content.as_bytes().to_vec()))
+    }
+
+    #[test]
+    fn test_default_extensions() {
+        let filter = ExtensionFilter::new(Some(CODE_FILE_EXTENSIONS), &Default::default()).unwrap();
+
+        assert!(accepts(&filter, "src/main.rs", ""));
+        assert!(accepts(&filter, "Cargo.toml", ""));
+        assert!(!accepts(&filter, "logo.png", ""));
+        assert!(accepts(
+            &filter,
+            "scripts/build",
+            "#!/usr/bin/env python3\n"
+        ));
+        assert!(accepts(&filter, "Makefile", ""));
+        assert!(!accepts(&filter, "LICENSE", "MIT License\n"));
+    }
+
+    #[test]
+    fn test_languages() {
+        let options = ExtensionOptions {
+            include_extensions: vec![".toml".to_string()],
+            exclude_extensions: vec!["pyi".to_string()],
+            languages: vec!["rust".to_string(), "python".to_string()],
+            ..Default::default()
+        };
+        let filter = ExtensionFilter::new(Some(CODE_FILE_EXTENSIONS), &options).unwrap();
+
+        assert!(accepts(&filter, "src/main.rs", ""));
+        assert!(accepts(&filter, "Cargo.toml", ""));
+        assert!(accepts(&filter, "setup.py", ""));
+        assert!(!accepts(&filter, "stubs.pyi", ""));
+        assert!(!accepts(&filter, "index.js", ""));
+        assert!(accepts(&filter, "run", "#!/usr/bin/python3.11 -u\n"));
+        assert!(!accepts(&filter, "run", "#!/bin/bash\n"));
+
+        let unknown = ExtensionOptions {
+            languages: vec!["cobol".to_string()],
+            ..Default::default()
+        };
+        assert!(ExtensionFilter::new(None, &unknown).is_err());
+    }
+
+    #[test]
+    fn test_all_extensions() {
+        let options = ExtensionOptions {
+            all_extensions: true,
+            exclude_extensions: vec!["lock".to_string()],
+            ..Default::default()
+        };
+        let filter = ExtensionFilter::new(Some(CODE_FILE_EXTENSIONS), &options).unwrap();
+
+        assert!(accepts(&filter, "logo.png", ""));
+        assert!(accepts(&filter, "LICENSE", ""));
+        assert!(!accepts(&filter, "Cargo.lock", ""));
+    }
+
+    #[test]
+    fn test_sniffing() {
+        assert_eq!(
+            shebang_interpreter(b"#!/usr/bin/env -S node --flag\n"),
+            Some("node".to_string())
+        );
+        assert_eq!(shebang_interpreter(b"#!/bin/sh"), Some("sh".to_string()));
+        assert_eq!(shebang_interpreter(b"echo"), None);
+        assert!(is_binary(b"PNG\0\x01"));
+        assert!(!is_binary("naïve text".as_bytes()));
+    }
+}
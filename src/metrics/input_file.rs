@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::metrics::churn_per_file::nodes_flat_list_to_tree;
+use crate::snapshot::Snapshot;
+use crate::tree::Tree;
+use crate::AnyError;
+
+pub const STDIN_PATH: &str = "-";
+
+/// Reads lines like `path,value`, `path<TAB>value` or `value path` (the output of `uniq -c`)
+/// from a file, or from stdin if the file is "-", and builds a tree with them under `folder`.
+/// If the content is a JSON snapshot (see `--output-file`), the saved tree and units are returned.
+pub fn tree_from_input_file(
+    input_file: &Path,
+    folder: PathBuf,
+) -> Result<(Tree, Option<String>), AnyError> {
+    let content = read_input(input_file)?;
+    if Snapshot::looks_like_json(&content) {
+        let Snapshot { tree, units } = Snapshot::from_json(&content)?;
+        Ok((tree, units))
+    } else {
+        Ok((tree_from_lines(&content, folder)?, None))
+    }
+}
+
+pub fn is_stdin(input_file: &Path) -> bool {
+    input_file.as_os_str() == STDIN_PATH
+}
+
+fn read_input(input_file: &Path) -> Result<String, AnyError> {
+    if is_stdin(input_file) {
+        let mut content = String::new();
+        std::io::stdin().lock().read_to_string(&mut content)?;
+        Ok(content)
+    } else {
+        Ok(fs::read_to_string(input_file)?)
+    }
+}
+
+fn tree_from_lines(content: &str, folder: PathBuf) -> Result<Tree, AnyError> {
+    let mut paths = Vec::new();
+    let mut sizes = HashMap::new();
+    let mut is_first_line = true;
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parsed = parse_line(line);
+        let may_be_header = is_first_line;
+        is_first_line = false;
+        match parsed {
+            Some((path, size)) => {
+                if size < 0 {
+                    return Err(format!("Negative value in line {}: {}", i + 1, line).into());
+                }
+                // repeated paths are accumulated, as `uniq -c` of an unsorted input would produce
+                let accumulated = sizes.entry(path.clone()).or_insert_with(|| {
+                    paths.push(path);
+                    0
+                });
+                *accumulated += size;
+            }
+            None if may_be_header => {
+                // probably a CSV header like "path,value"
+            }
+            None => {
+                return Err(format!("Can't parse line {}: {}", i + 1, line).into());
+            }
+        }
+    }
+    let nodes = paths
+        .into_iter()
+        .map(|path| {
+            let size = sizes[&path];
+            Tree::new_from_size(path, size)
+        })
+        .collect();
+    nodes_flat_list_to_tree(nodes, folder)
+}
+
+/// Returns the normalized path and the value of a line. Lines with a comma or a tab are parsed
+/// first as `path,value` or `path<TAB>value`, because their paths may have spaces, and then as
+/// `value path`, because a path may also have commas.
+fn parse_line(line: &str) -> Option<(String, i64)> {
+    let value_first = || {
+        line.split_once(char::is_whitespace)
+            .and_then(|(value, path)| Some((path, value.parse::<i64>().ok()?)))
+    };
+    let value_last = line
+        .rsplit_once([',', '\t'])
+        .and_then(|(path, value)| Some((path, value.trim().parse::<i64>().ok()?)));
+    let (path, value) = value_last.or_else(value_first)?;
+    let path = normalize_path(path.trim());
+    if path.is_empty() {
+        None
+    } else {
+        Some((path, value))
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    let mut path = path.trim_start_matches('/');
+    while let Some(stripped) = path.strip_prefix("./") {
+        path = stripped;
+    }
+    path.trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_formats() {
+        let expected = Some(("src/main.rs".to_string(), 12));
+        assert_eq!(parse_line("src/main.rs,12"), expected);
+        assert_eq!(parse_line("src/main.rs\t12"), expected);
+        assert_eq!(parse_line("12 src/main.rs"), expected);
+        assert_eq!(parse_line("12 ./src/main.rs"), expected);
+        assert_eq!(parse_line("path,value"), None);
+        assert_eq!(
+            parse_line("2023 report.md,5"),
+            Some(("2023 report.md".to_string(), 5))
+        );
+        assert_eq!(parse_line("3 a,b.rs"), Some(("a,b.rs".to_string(), 3)));
+    }
+
+    #[test]
+    fn test_tree_from_uniq_output() {
+        let content = "
+              3 src/main.rs
+              1 src/lib.rs
+              2 src/main.rs
+              4 README.md
+        ";
+        let tree = tree_from_lines(content, ".".into()).unwrap();
+
+        #[rustfmt::skip]
+        let expected = Tree::new_from_computed_size(".".into(), 10, vec![
+            Tree::new_from_computed_size("./src".into(), 6, vec![
+                Tree::new_from_size("./src/main.rs".into(), 5),
+                Tree::new_from_size("./src/lib.rs".into(), 1),
+            ]),
+            Tree::new_from_size("./README.md".into(), 4),
+        ]);
+        assert!(tree.recursive_equals(&expected), "{:#?}", tree);
+    }
+
+    #[test]
+    fn test_tree_from_csv_with_header() {
+        let content = "path,value\nsrc/main.rs,3\n";
+        let tree = tree_from_lines(content, ".".into()).unwrap();
+        assert_eq!(tree.size(), 3);
+
+        let content = "src/main.rs,3\nsrc/lib.rs,unknown\n";
+        assert!(tree_from_lines(content, ".".into()).is_err());
+    }
+}
@@ -1,96 +1,55 @@
 use std::fs;
-use std::fs::File;
-use std::io::BufRead;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
-use ignore::gitignore::Gitignore;
-use macroquad::prelude::{error, warn};
-
-use crate::metrics::bytes_per_file::has_allowed_extension;
-use crate::metrics::word_mentions::{CODE_FILE_EXTENSIONS, TEXT_FILE_EXTENSIONS};
+use crate::metrics::extensions::{
+    is_binary, ExtensionFilter, ExtensionOptions, CODE_FILE_EXTENSIONS,
+};
+use crate::metrics::walk::{measure_files, WalkOptions};
+use crate::progress::Progress;
 use crate::tree::Tree;
 use crate::AnyError;
 
-pub fn lines_per_file(folder: &PathBuf) -> Result<Option<Tree>, AnyError> {
-    lines_per_file_recursive(folder, None)
-}
-
-pub fn lines_per_file_recursive(
+/// Considers the files of source code by default. Binary files are always skipped.
+pub fn lines_per_file(
     folder: &PathBuf,
-    higher_gitignore: Option<&Gitignore>,
+    extensions: &ExtensionOptions,
+    options: &WalkOptions,
+    progress: &Progress,
 ) -> Result<Option<Tree>, AnyError> {
-    let path = Path::new(folder);
-    let path_str = folder.to_string_lossy().to_string();
-    if path.is_symlink() {
-        warn!("{} is a symlink and will be ignored", path_str);
-        Ok(None)
-    } else if Path::new(folder).is_file() {
-        if has_allowed_extension(folder, CODE_FILE_EXTENSIONS) {
-            Ok(Some(Tree::new_from_size(
-                path_str,
-                count_lines_in_file(folder)? as i64,
-            )))
-        } else {
-            Ok(None)
-        }
-    } else if Path::new(folder).is_dir() {
-        let mut nodes = Vec::new();
-        let mut gitignore = None;
-        for entry_res in fs::read_dir(folder)? {
-            let entry = entry_res?;
-            if entry.path().ends_with(".gitignore") {
-                let (new_gitignore, error) = Gitignore::new(entry.path());
-                if let Some(e) = error {
-                    error!("Error parsing .gitignore file: {}", e);
-                }
-                gitignore = Some(new_gitignore)
-            }
-        }
-        let chosen_gitignore = gitignore.as_ref().or(higher_gitignore);
-        for entry_res in fs::read_dir(folder)? {
-            let entry = entry_res?;
-            let should_ignore = if let Some(gitignore) = chosen_gitignore {
-                gitignore
-                    .matched(&entry.path(), entry.path().is_dir())
-                    .is_ignore()
+    let filter = ExtensionFilter::new(Some(CODE_FILE_EXTENSIONS), extensions)?;
+    measure_files(folder, options, progress, |file| {
+        if filter.accepts(file) {
+            let content = fs::read(file)?;
+            if is_binary(&content) {
+                Ok(None)
             } else {
-                false
-            };
-            if !should_ignore {
-                let node_option = lines_per_file_recursive(&entry.path(), chosen_gitignore)?;
-                if let Some(node) = node_option {
-                    nodes.push(node);
-                }
+                Ok(Some(count_lines(&content) as i64))
             }
+        } else {
+            Ok(None)
         }
-        let mut parent = Tree::new_from_children(path_str, nodes);
-        parent.get_or_compute_size();
-        Ok(Some(parent))
-    } else {
-        error!(
-            "{} is not a file nor a directory nor a symlink. Ignoring...",
-            path_str
-        );
-        Ok(None)
-    }
+    })
 }
 
-fn count_lines_in_file(file: &PathBuf) -> Result<usize, AnyError> {
-    if is_text_file(file) {
-        let file_handle = File::open(file)?;
-        let lines = std::io::BufReader::new(file_handle).lines();
-        let mut count = 0;
-        for _ in lines {
-            // this is so stupid. However, it's not obvious to me that I could write something
-            // faster than this, due to the buffering provided by BufReader
-            count += 1;
-        }
-        Ok(count)
+/// Counts like `BufRead::lines`: a last line without a line break is also counted.
+pub(crate) fn count_lines(content: &[u8]) -> usize {
+    let line_breaks = content.iter().filter(|byte| **byte == b'\n').count();
+    if content.last().is_some_and(|last| *last != b'\n') {
+        line_breaks + 1
     } else {
-        Err(format!("Not a text file: {}", file.to_string_lossy().to_string()).into())
+        line_breaks
     }
 }
 
-fn is_text_file(file: &PathBuf) -> bool {
-    has_allowed_extension(file, TEXT_FILE_EXTENSIONS)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_lines() {
+        assert_eq!(count_lines(b""), 0);
+        assert_eq!(count_lines(b"\n"), 1);
+        assert_eq!(count_lines(b"a\nb"), 2);
+        assert_eq!(count_lines(b"a\nb\n"), 2);
+    }
 }
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use crate::git_churn::ChurnOptions;
+use crate::metrics::churn_per_file::{git_churn_per_file, nodes_flat_list_to_tree};
+use crate::metrics::extensions::ExtensionOptions;
+use crate::metrics::lines::lines_per_file;
+use crate::metrics::walk::WalkOptions;
+use crate::tree::Tree;
+use crate::AnyError;
+
+/// Multiplies the churn of each file by its line count, to find big files that change often.
+/// The files skipped by `extensions` or `walk_options` are left out.
+pub fn refactor_per_file(
+    folder: PathBuf,
+    extensions: &ExtensionOptions,
+    walk_options: &WalkOptions,
+    options: &ChurnOptions,
+) -> Result<Tree, AnyError> {
+    let churn = git_churn_per_file(folder.clone(), options)?;
+    let lines = lines_per_file(&folder, extensions, walk_options, &options.progress)?
+        .ok_or("no source code files to count lines")?;
+    multiply_by_path(&churn, &lines, folder)
+}
+
+/// Files that are present in only one of the trees are dropped, e.g. deleted files that still
+/// have churn, or files never committed that still have lines. They would be multiplied by 0 anyway.
+fn multiply_by_path(left: &Tree, right: &Tree, folder: PathBuf) -> Result<Tree, AnyError> {
+    let right_sizes = right.leaf_sizes_by_path();
+    let mut products = left
+        .leaf_sizes_by_path()
+        .into_iter()
+        .filter_map(|(path, left_size)| {
+            let right_size = right_sizes.get(&path)?;
+            Some((path, left_size * right_size))
+        })
+        .filter(|(_, product)| *product > 0)
+        .collect::<Vec<_>>();
+    products.sort();
+    let nodes = products
+        .into_iter()
+        .map(|(path, product)| Tree::new_from_size(path, product))
+        .collect();
+    nodes_flat_list_to_tree(nodes, folder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_different_naming_schemes() {
+        #[rustfmt::skip]
+        let churn = Tree::new_from_children(".".into(), vec![
+            Tree::new_from_children("./src".into(), vec![
+                Tree::new_from_size("./src/main.rs".into(), 3),
+                Tree::new_from_size("./src/deleted.rs".into(), 5),
+            ]),
+        ]);
+        #[rustfmt::skip]
+        let lines = Tree::new_from_children("./".into(), vec![
+            Tree::new_from_children("./src".into(), vec![
+                Tree::new_from_size("./src/main.rs".into(), 100),
+                Tree::new_from_size("./src/uncommitted.rs".into(), 20),
+            ]),
+        ]);
+
+        let tree = multiply_by_path(&churn, &lines, ".".into()).unwrap();
+
+        #[rustfmt::skip]
+        let expected = Tree::new_from_computed_size(".".into(), 300, vec![
+            Tree::new_from_computed_size("./src".into(), 300, vec![
+                Tree::new_from_size("./src/main.rs".into(), 300),
+            ]),
+        ]);
+        assert!(tree.recursive_equals(&expected), "{:#?}", tree);
+    }
+}
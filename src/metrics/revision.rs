@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+
+use crate::metrics::churn_per_file::nodes_flat_list_to_tree;
+use crate::metrics::extensions::{
+    is_binary, ExtensionFilter, ExtensionOptions, CODE_FILE_EXTENSIONS,
+};
+use crate::metrics::lines::count_lines;
+use crate::metrics::walk::{PathFilter, WalkOptions};
+use crate::progress::Progress;
+use crate::tree::Tree;
+use crate::AnyError;
+
+/// Git file mode of symlinks, which are ignored like when walking the working directory.
+const SYMLINK_FILE_MODE: i32 = 0o120000;
+
+/// The measure of each blob, by its id, so that the blobs that didn't change between revisions
+/// (e.g. the frames of a timeline) are only read once. A cache must only be used with one metric.
+/// None means that the blob can't be measured, e.g. it's binary.
+pub type BlobCache = HashMap<Oid, Option<i64>>;
+
+/// Like `bytes_per_file::bytes_per_file`, but reading the files of a git revision (e.g. a commit,
+/// a tag or a branch) from the object database of the repo in `folder`, without checking it out.
+pub fn bytes_per_file_at(
+    folder: &PathBuf,
+    rev: &str,
+    extensions: &ExtensionOptions,
+    walk_options: &WalkOptions,
+    cache: &mut BlobCache,
+    progress: &Progress,
+) -> Result<Tree, AnyError> {
+    let filter = ExtensionFilter::new(None, extensions)?;
+    let paths = PathFilter::new(walk_options)?;
+    let repo = Repository::open(folder)?;
+    let odb = repo.odb()?;
+    let nodes = files_at(
+        &repo,
+        rev,
+        &paths,
+        cache,
+        progress,
+        |path, blob_id| accepts(&repo, &filter, path, blob_id),
+        |blob_id| {
+            let (size, _) = odb.read_header(blob_id)?;
+            Ok(Some(size as i64))
+        },
+    )?;
+    nodes_flat_list_to_tree(nodes, folder.clone())
+}
+
+/// Like `lines::lines_per_file`, but reading the files of a git revision (e.g. a commit, a tag or
+/// a branch) from the object database of the repo in `folder`, without checking it out.
+pub fn lines_per_file_at(
+    folder: &PathBuf,
+    rev: &str,
+    extensions: &ExtensionOptions,
+    walk_options: &WalkOptions,
+    cache: &mut BlobCache,
+    progress: &Progress,
+) -> Result<Option<Tree>, AnyError> {
+    let filter = ExtensionFilter::new(Some(CODE_FILE_EXTENSIONS), extensions)?;
+    let paths = PathFilter::new(walk_options)?;
+    let repo = Repository::open(folder)?;
+    let nodes = files_at(
+        &repo,
+        rev,
+        &paths,
+        cache,
+        progress,
+        |path, blob_id| accepts(&repo, &filter, path, blob_id),
+        |blob_id| {
+            let blob = repo.find_blob(blob_id)?;
+            if is_binary(blob.content()) {
+                Ok(None)
+            } else {
+                Ok(Some(count_lines(blob.content()) as i64))
+            }
+        },
+    )?;
+    if nodes.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(nodes_flat_list_to_tree(nodes, folder.clone())?))
+    }
+}
+
+fn accepts(repo: &Repository, filter: &ExtensionFilter, path: &str, blob_id: Oid) -> bool {
+    filter.accepts_with(Path::new(path), || {
+        Some(repo.find_blob(blob_id).ok()?.content().to_vec())
+    })
+}
+
+/// Returns a leaf for each file of the revision accepted by `paths` and `accepts` for which
+/// `measure` returns a size, named with its path relative to the root of the repo. Only the blobs
+/// missing from `cache` are measured.
+fn files_at(
+    repo: &Repository,
+    rev: &str,
+    paths: &PathFilter,
+    cache: &mut BlobCache,
+    progress: &Progress,
+    accepts: impl Fn(&str, Oid) -> bool,
+    measure: impl Fn(Oid) -> Result<Option<i64>, AnyError>,
+) -> Result<Vec<Tree>, AnyError> {
+    let tree = repo.revparse_single(rev)?.peel_to_tree()?;
+    let mut nodes = Vec::new();
+    let mut result = Ok(());
+    tree.walk(TreeWalkMode::PreOrder, |folder, entry| {
+        if progress.is_cancelled() {
+            return TreeWalkResult::Abort;
+        }
+        if entry.kind() != Some(ObjectType::Blob) || entry.filemode() == SYMLINK_FILE_MODE {
+            // folders are walked anyway, and submodules and symlinks are ignored
+            return TreeWalkResult::Ok;
+        }
+        let path = format!("{}{}", folder, String::from_utf8_lossy(entry.name_bytes()));
+        if !paths.accepts(Path::new(&path)) || !accepts(&path, entry.id()) {
+            return TreeWalkResult::Ok;
+        }
+        let size = match cache.get(&entry.id()) {
+            Some(size) => *size,
+            None => match measure(entry.id()) {
+                Ok(size) => *cache.entry(entry.id()).or_insert(size),
+                Err(e) => {
+                    result = Err(e);
+                    return TreeWalkResult::Abort;
+                }
+            },
+        };
+        if let Some(size) = size {
+            progress.add_file();
+            nodes.push(Tree::new_from_size(path, size));
+        }
+        TreeWalkResult::Ok
+    })?;
+    result?;
+    progress.check_cancelled()?;
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_repo::TestRepo;
+
+    fn repo_with_two_commits(name: &str) -> TestRepo {
+        let mut repo = TestRepo::new(name);
+        repo.commit(&[("src/main.rs", "fn main() {}\n")], "first");
+        repo.commit(
+            &[
+                ("src/main.rs", "fn main() {\n    run();\n}\n"),
+                ("src/lib.rs", "pub fn run() {}\n"),
+                ("Cargo.lock", "version = 3\n"),
+            ],
+            "second",
+        );
+        repo
+    }
+
+    #[test]
+    fn test_lines_at_head() {
+        let repo = repo_with_two_commits("revision-head");
+        let progress = Progress::new();
+        let tree = lines_per_file_at(
+            &repo.path,
+            "HEAD",
+            &Default::default(),
+            &Default::default(),
+            &mut BlobCache::new(),
+            &progress,
+        )
+        .unwrap()
+        .unwrap();
+
+        let sizes = tree.leaf_sizes_by_path();
+        assert_eq!(sizes.get("src/main.rs"), Some(&3));
+        assert_eq!(sizes.get("src/lib.rs"), Some(&1));
+        assert_eq!(sizes.get("Cargo.lock"), None);
+    }
+
+    #[test]
+    fn test_cached_blobs() {
+        let repo = repo_with_two_commits("revision-cache");
+        let progress = Progress::new();
+        let mut cache = BlobCache::new();
+        let mut lines_at = |rev| {
+            let options = Default::default();
+            lines_per_file_at(
+                &repo.path,
+                rev,
+                &options,
+                &Default::default(),
+                &mut cache,
+                &progress,
+            )
+            .unwrap()
+            .unwrap()
+        };
+        let first = lines_at("HEAD~1");
+        let second = lines_at("HEAD");
+
+        let uncached = lines_per_file_at(
+            &repo.path,
+            "HEAD",
+            &Default::default(),
+            &Default::default(),
+            &mut BlobCache::new(),
+            &progress,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(first.size(), 1);
+        assert_eq!(second.leaf_sizes_by_path(), uncached.leaf_sizes_by_path());
+        assert_eq!(cache.len(), 3);
+    }
+}
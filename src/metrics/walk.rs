@@ -0,0 +1,234 @@
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+use macroquad::prelude::warn;
+
+use crate::metrics::churn_per_file::nodes_flat_list_to_tree;
+use crate::progress::Progress;
+use crate::tree::Tree;
+use crate::AnyError;
+
+/// Which files of the input folder are considered by the metrics that read the filesystem
+#[derive(Clone, Debug, Default)]
+pub struct WalkOptions {
+    /// only files matching any of these globs (relative to the input folder), e.g. `src/**` or
+    /// `*.rs`, if they are not ignored. All files if empty
+    pub include: Vec<String>,
+    /// skip files and folders matching any of these globs, e.g. `*.lock` or `tests`
+    pub exclude: Vec<String>,
+    /// also consider the files ignored by .gitignore, .ignore, .git/info/exclude and the global
+    /// git excludes file
+    pub no_ignore: bool,
+}
+
+/// The `include` and `exclude` globs of `WalkOptions`, to filter paths relative to the input
+/// folder, both when walking the filesystem and when reading a git revision.
+#[derive(Clone)]
+pub struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: Gitignore,
+}
+
+impl PathFilter {
+    pub fn new(options: &WalkOptions) -> Result<Self, AnyError> {
+        let include = if options.include.is_empty() {
+            None
+        } else {
+            let mut globs = GlobSetBuilder::new();
+            for glob in &options.include {
+                globs.add(Glob::new(glob)?);
+            }
+            Some(globs.build()?)
+        };
+        let mut exclude = GitignoreBuilder::new("");
+        for glob in &options.exclude {
+            exclude.add_line(None, glob)?;
+        }
+        Ok(Self {
+            include,
+            exclude: exclude.build()?,
+        })
+    }
+
+    /// Whether a file or folder is excluded, without looking at its parents.
+    fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        self.exclude.matched(path, is_dir).is_ignore()
+    }
+
+    /// Whether a file is included and neither it nor its folders are excluded.
+    pub fn accepts(&self, file: &Path) -> bool {
+        !self
+            .exclude
+            .matched_path_or_any_parents(file, false)
+            .is_ignore()
+            && match &self.include {
+                Some(include) => include.is_match(file),
+                None => true,
+            }
+    }
+}
+
+/// Calls `visit` with every file under `folder` that is not ignored, in a stable order. Nested
+/// ignore files are combined like git does, even if `folder` is not a git repo. Hidden files are
+/// considered, but the .git folder is skipped, and symlinks are not followed. The `include` globs
+/// can't bring back an ignored file.
+pub fn walk_files(
+    folder: &Path,
+    options: &WalkOptions,
+    progress: &Progress,
+    mut visit: impl FnMut(&Path) -> Result<(), AnyError>,
+) -> Result<(), AnyError> {
+    let filter = PathFilter::new(options)?;
+    let excluded_folders = filter.clone();
+    let root = folder.to_path_buf();
+    let walker = WalkBuilder::new(folder)
+        .standard_filters(!options.no_ignore)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(move |entry| {
+            let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+            let is_dir = entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir());
+            entry.file_name() != ".git"
+                && (entry.depth() == 0 || !excluded_folders.is_excluded(relative, is_dir))
+        })
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build();
+    for entry in walker {
+        progress.check_cancelled()?;
+        let entry = entry?;
+        match entry.file_type() {
+            Some(file_type) if file_type.is_symlink() => {
+                warn!(
+                    "{} is a symlink and will be ignored",
+                    entry.path().display()
+                );
+            }
+            Some(file_type) if file_type.is_file() => {
+                let relative = entry.path().strip_prefix(folder)?;
+                // the input folder can be a single file
+                let relative = if entry.depth() == 0 {
+                    Path::new(entry.file_name())
+                } else {
+                    relative
+                };
+                if filter.accepts(relative) {
+                    visit(entry.path())?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Builds a tree with a leaf for each file for which `measure` returns a size. Returns None if no
+/// file was measured. If `folder` is a file, the tree is only that leaf.
+pub fn measure_files(
+    folder: &PathBuf,
+    options: &WalkOptions,
+    progress: &Progress,
+    mut measure: impl FnMut(&Path) -> Result<Option<i64>, AnyError>,
+) -> Result<Option<Tree>, AnyError> {
+    let folder_str = folder.to_string_lossy().to_string();
+    let mut nodes = Vec::new();
+    walk_files(folder, options, progress, |file| {
+        if let Some(size) = measure(file)? {
+            progress.add_file();
+            let relative = file.strip_prefix(folder)?.to_string_lossy().to_string();
+            if relative.is_empty() {
+                nodes.push(Tree::new_from_size(folder_str.clone(), size));
+            } else {
+                nodes.push(Tree::new_from_size(relative, size));
+            }
+        }
+        Ok(())
+    })?;
+    if nodes.is_empty() {
+        Ok(None)
+    } else if folder.is_file() {
+        Ok(nodes.pop())
+    } else {
+        Ok(Some(nodes_flat_list_to_tree(nodes, folder.clone())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn walked_files(options: &WalkOptions) -> Vec<String> {
+        let mut files = Vec::new();
+        walk_files(Path::new("."), options, &Progress::new(), |file| {
+            files.push(file.to_string_lossy().to_string());
+            Ok(())
+        })
+        .unwrap();
+        files
+    }
+
+    #[test]
+    fn test_ignored_files() {
+        let files = walked_files(&WalkOptions::default());
+
+        assert!(files.contains(&"./src/main.rs".to_string()));
+        assert!(files.contains(&"./.gitignore".to_string()));
+        assert!(!files.iter().any(|file| file.starts_with("./target/")));
+        assert!(!files.iter().any(|file| file.starts_with("./.git/")));
+    }
+
+    #[test]
+    fn test_include_and_exclude() {
+        let files = walked_files(&WalkOptions {
+            include: vec!["src/**".to_string()],
+            exclude: vec!["src/ui".to_string()],
+            no_ignore: false,
+        });
+
+        assert!(files.contains(&"./src/main.rs".to_string()));
+        assert!(!files.contains(&"./README.md".to_string()));
+        assert!(!files.iter().any(|file| file.starts_with("./src/ui/")));
+    }
+
+    #[test]
+    fn test_include_does_not_bring_back_ignored_files() {
+        let folder = std::env::temp_dir().join(format!("code-map-walk-{}", std::process::id()));
+        fs::create_dir_all(folder.join("src")).unwrap();
+        fs::write(folder.join(".gitignore"), "generated.rs\n").unwrap();
+        fs::write(folder.join("src/main.rs"), "").unwrap();
+        fs::write(folder.join("src/generated.rs"), "").unwrap();
+        let options = WalkOptions {
+            include: vec!["src/**".to_string()],
+            ..Default::default()
+        };
+
+        let mut files = Vec::new();
+        let result = walk_files(&folder, &options, &Progress::new(), |file| {
+            files.push(file.strip_prefix(&folder)?.to_string_lossy().to_string());
+            Ok(())
+        });
+        fs::remove_dir_all(&folder).unwrap();
+
+        result.unwrap();
+        assert_eq!(files, vec!["src/main.rs"]);
+    }
+
+    #[test]
+    fn test_path_filter() {
+        let filter = PathFilter::new(&WalkOptions {
+            include: vec!["*.rs".to_string()],
+            exclude: vec!["vendor".to_string()],
+            no_ignore: false,
+        })
+        .unwrap();
+
+        assert!(filter.accepts(Path::new("src/main.rs")));
+        assert!(!filter.accepts(Path::new("README.md")));
+        assert!(!filter.accepts(Path::new("vendor/lib/lib.rs")));
+    }
+}
@@ -1,30 +1,31 @@
 use std::collections::HashMap;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
-use macroquad::prelude::{error, warn};
-
-use crate::metrics::bytes_per_file::has_allowed_extension;
+use crate::metrics::extensions::{
+    is_binary, ExtensionFilter, ExtensionOptions, TEXT_FILE_EXTENSIONS,
+};
+use crate::metrics::walk::{walk_files, WalkOptions};
+use crate::progress::Progress;
 use crate::tree::Tree;
 use crate::AnyError;
 
-#[rustfmt::skip]
-pub const TEXT_FILE_EXTENSIONS: &[&str] = &[
-    "c", "cc", "cpp", "cs", "css", "go", ".gitignore", "h", "hpp", "html",
-    "java", "js", "json", "jsx", "m", "mm", "md", "pbproj", "php", "py",
-    "rs", "sh", "swift", "ts", "tsx", "txt", "xml", "yaml", "yml",
-];
-
-#[rustfmt::skip]
-pub const CODE_FILE_EXTENSIONS: &[&str] = &[
-    "c", "cc", "cpp", "cs", "css", "go", "h", "hpp", "html",
-    "java", "js", "json", "jsx", "m", "mm", "md", "pbproj", "php", "py",
-    "rs", "sh", "swift", "ts", "tsx", "yaml", "yml",
-];
-
-pub fn word_mentions(folder: &PathBuf) -> Result<Tree, AnyError> {
+/// Considers the text files by default. Binary files are always skipped.
+pub fn word_mentions(
+    folder: &Path,
+    extensions: &ExtensionOptions,
+    options: &WalkOptions,
+    progress: &Progress,
+) -> Result<Tree, AnyError> {
+    let filter = ExtensionFilter::new(Some(TEXT_FILE_EXTENSIONS), extensions)?;
     let mut mentions = HashMap::new();
-    word_mentions_recursive(folder, &mut mentions)?;
+    walk_files(folder, options, progress, |file| {
+        if filter.accepts(file) {
+            progress.add_file();
+            count_word_mentions_in_file(file, &mut mentions)?;
+        }
+        Ok(())
+    })?;
     let mut nodes = Vec::new();
     nodes.reserve(mentions.len());
     for (word, count) in mentions {
@@ -33,35 +34,13 @@ pub fn word_mentions(folder: &PathBuf) -> Result<Tree, AnyError> {
     Ok(Tree::new_from_children("".to_string(), nodes))
 }
 
-fn word_mentions_recursive(
-    folder: &PathBuf,
-    mentions: &mut HashMap<String, i64>,
-) -> Result<(), AnyError> {
-    let path = Path::new(folder);
-    let path_str = folder.to_string_lossy().to_string();
-    if path.is_symlink() {
-        warn!("{} is a symlink and will be ignored", path_str);
-    } else if Path::new(folder).is_file() {
-        count_word_mentions_in_file(folder, mentions)?;
-    } else if Path::new(folder).is_dir() {
-        for entry in fs::read_dir(folder)? {
-            word_mentions_recursive(&entry?.path(), mentions)?;
-        }
-    } else {
-        error!(
-            "{} is not a file nor a directory nor a symlink. Ignoring...",
-            path_str
-        );
-    }
-    Ok(())
-}
-
 fn count_word_mentions_in_file(
-    file: &PathBuf,
+    file: &Path,
     mentions: &mut HashMap<String, i64>,
 ) -> Result<(), AnyError> {
-    if is_text_file(file) {
-        let file_content = fs::read_to_string(file)?;
+    let content = fs::read(file)?;
+    if !is_binary(&content) {
+        let file_content = String::from_utf8_lossy(&content);
         for word in file_content.split_terminator(|c: char| !c.is_alphanumeric() && c != '_') {
             if !word.is_empty() {
                 let count = mentions.entry(word.to_string()).or_insert(0);
@@ -71,7 +50,3 @@ fn count_word_mentions_in_file(
     }
     Ok(())
 }
-
-fn is_text_file(file: &PathBuf) -> bool {
-    has_allowed_extension(file, TEXT_FILE_EXTENSIONS)
-}
@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::AnyError;
+
+pub const CANCELLED_MESSAGE: &str = "the computation was cancelled";
+
+/// Counters that a metric updates while it's being computed in another thread, so that the UI
+/// can show how far it got. The UI can also ask the metric to stop early with `cancel`.
+#[derive(Debug, Default)]
+pub struct Progress {
+    commits: AtomicUsize,
+    total_commits: AtomicUsize,
+    files: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_commit(&self) {
+        self.commits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set_total_commits(&self, total: usize) {
+        self.total_commits.store(total, Ordering::Relaxed);
+    }
+
+    pub fn add_file(&self) {
+        self.files.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn commits(&self) -> usize {
+        self.commits.load(Ordering::Relaxed)
+    }
+
+    /// 0 until the commits to process are known
+    pub fn total_commits(&self) -> usize {
+        self.total_commits.load(Ordering::Relaxed)
+    }
+
+    pub fn files(&self) -> usize {
+        self.files.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Meant to be used with `?` in the loops of the metrics, to stop as soon as possible.
+    pub fn check_cancelled(&self) -> Result<(), AnyError> {
+        if self.is_cancelled() {
+            Err(CANCELLED_MESSAGE.into())
+        } else {
+            Ok(())
+        }
+    }
+}
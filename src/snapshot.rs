@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tree::Tree;
+use crate::AnyError;
+
+/// A computed tree that can be saved to a JSON file and loaded back later, to avoid recomputing
+/// expensive metrics like churn-per-file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub units: Option<String>,
+    pub tree: Tree,
+}
+
+impl Snapshot {
+    pub fn new(tree: Tree, units: &str) -> Self {
+        Self {
+            units: Some(units.to_string()),
+            tree,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AnyError> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<String, AnyError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Folders don't need to have a size in the JSON, it will be computed from their children.
+    /// Files are read with `input_file::tree_from_input_file`, which also accepts other formats.
+    pub fn from_json(json: &str) -> Result<Self, AnyError> {
+        let mut snapshot: Snapshot = serde_json::from_str(json)?;
+        snapshot.tree.get_or_compute_size();
+        Ok(snapshot)
+    }
+
+    pub fn looks_like_json(content: &str) -> bool {
+        content.trim_start().starts_with('{')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        #[rustfmt::skip]
+        let tree = Tree::new_from_children(".".into(), vec![
+            Tree::new_from_children("./src".into(), vec![
+                Tree::new_from_size("./src/main.rs".into(), 5),
+                Tree::new_from_size("./src/lib.rs".into(), 1),
+            ]),
+            Tree::new_from_size("./README.md".into(), 4),
+        ]);
+        let snapshot = Snapshot::new(tree.clone(), "lines");
+
+        let loaded = Snapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+
+        assert_eq!(loaded.units, Some("lines".to_string()));
+        assert!(loaded.tree.recursive_equals(&tree));
+    }
+
+    #[test]
+    fn test_sizes_of_folders_are_optional() {
+        let json = r#"{"tree": {"name": ".", "children": [
+            {"name": "./a", "size": 2},
+            {"name": "./b", "size": 3}
+        ]}}"#;
+
+        let loaded = Snapshot::from_json(json).unwrap();
+
+        assert_eq!(loaded.units, None);
+        assert_eq!(loaded.tree.size(), 5);
+    }
+}
@@ -0,0 +1,71 @@
+//! A throwaway git repo for the tests that read the history, so that they don't depend on the
+//! history or the files of the code-map repo itself.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use git2::{Oid, Repository, Signature, Time};
+
+/// The date of the first commit, 2023-01-01. Each commit is one day later than the previous one.
+const FIRST_COMMIT_SECONDS: i64 = 1672531200;
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A git repo in a temporary folder, which is removed when dropped.
+pub struct TestRepo {
+    pub path: PathBuf,
+    pub repo: Repository,
+    commits: i64,
+}
+
+impl TestRepo {
+    /// `name` must be unique among the tests, because they run in parallel.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("code-map-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let repo = Repository::init(&path).unwrap();
+        Self {
+            path,
+            repo,
+            commits: 0,
+        }
+    }
+
+    /// Writes the files (path relative to the repo and content) into the working directory and
+    /// commits them on top of HEAD. The other files of the previous commit are kept.
+    pub fn commit(&mut self, files: &[(&str, &str)], message: &str) -> Oid {
+        let mut index = self.repo.index().unwrap();
+        for (file, content) in files {
+            let path = self.path.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            index.add_path(Path::new(file)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = self.repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let time = Time::new(FIRST_COMMIT_SECONDS + self.commits * SECONDS_PER_DAY, 0);
+        let signature = Signature::new("Test", "test@example.com", &time).unwrap();
+        let parent = self
+            .repo
+            .head()
+            .ok()
+            .map(|head| head.peel_to_commit().unwrap());
+        let parents = parent.iter().collect::<Vec<_>>();
+        self.commits += 1;
+        self.repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+    }
+}
+
+impl Drop for TestRepo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use git2::{Oid, Repository};
+
+use crate::git_churn::format_date;
+use crate::progress::Progress;
+use crate::tree::Tree;
+use crate::AnyError;
+
+const SHORT_ID_LENGTH: usize = 7;
+
+/// The tree of a metric at one commit of the history.
+pub struct Frame {
+    pub commit: String,
+    pub date: String,
+    pub summary: String,
+    pub tree: Tree,
+}
+
+impl Frame {
+    pub fn label(&self) -> String {
+        format!("{} {} {}", self.date, self.commit, self.summary)
+    }
+}
+
+/// Samples `count` commits evenly along the first-parent history of HEAD of the repo in `folder`,
+/// from the oldest to the newest, and computes a tree for each of them with `compute`, which
+/// receives the commit id. Commits for which `compute` returns None (e.g. there were no source
+/// files yet) are skipped.
+pub fn compute_timeline(
+    folder: &Path,
+    count: usize,
+    progress: &Progress,
+    mut compute: impl FnMut(&str) -> Result<Option<Tree>, AnyError>,
+) -> Result<Vec<Frame>, AnyError> {
+    let repo = Repository::open(folder)?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.simplify_first_parent()?;
+    let mut commits = revwalk.collect::<Result<Vec<Oid>, _>>()?;
+    commits.reverse();
+
+    let sampled = sample_evenly(commits.len(), count);
+    progress.set_total_commits(sampled.len());
+    let mut frames = Vec::new();
+    for index in sampled {
+        progress.check_cancelled()?;
+        let commit = repo.find_commit(commits[index])?;
+        let id = commit.id().to_string();
+        if let Some(tree) = compute(&id)? {
+            frames.push(Frame {
+                commit: id[..SHORT_ID_LENGTH].to_string(),
+                date: format_date(commit.time().seconds()),
+                summary: String::from_utf8_lossy(commit.summary_bytes().unwrap_or_default())
+                    .to_string(),
+                tree,
+            });
+        }
+        progress.add_commit();
+    }
+    Ok(frames)
+}
+
+/// Indexes of `count` items evenly spread among `total` items, always including the last one.
+fn sample_evenly(total: usize, count: usize) -> Vec<usize> {
+    if count >= total {
+        (0..total).collect()
+    } else if count == 0 {
+        Vec::new()
+    } else if count == 1 {
+        vec![total - 1]
+    } else {
+        (0..count).map(|i| i * (total - 1) / (count - 1)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_repo::TestRepo;
+
+    #[test]
+    fn test_sample_evenly() {
+        assert_eq!(sample_evenly(10, 3), vec![0, 4, 9]);
+        assert_eq!(sample_evenly(10, 2), vec![0, 9]);
+        assert_eq!(sample_evenly(10, 1), vec![9]);
+        assert_eq!(sample_evenly(3, 5), vec![0, 1, 2]);
+        assert_eq!(sample_evenly(0, 5), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_timeline() {
+        let mut repo = TestRepo::new("timeline");
+        repo.commit(&[("README.md", "# test\n")], "readme");
+        let first = repo.commit(&[("src/main.rs", "fn main() {}\n")], "first");
+        repo.commit(&[("src/main.rs", "fn main() {\n}\n")], "second");
+        let last = repo.commit(&[("src/lib.rs", "\n")], "last");
+
+        let progress = Progress::new();
+        let frames = compute_timeline(&repo.path, 3, &progress, |id| {
+            // 3 of the 4 commits are sampled: the readme one, which is skipped, first and last
+            if id == first.to_string() || id == last.to_string() {
+                Ok(Some(Tree::new_from_size(id.to_string(), 1)))
+            } else {
+                Ok(None)
+            }
+        })
+        .unwrap();
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].commit, first.to_string()[..SHORT_ID_LENGTH]);
+        assert_eq!(
+            frames[0].label(),
+            format!("2023-01-02 {} first", frames[0].commit)
+        );
+        assert_eq!(frames[1].commit, last.to_string()[..SHORT_ID_LENGTH]);
+        assert_eq!(frames[1].summary, "last");
+        assert_eq!(progress.commits(), 3);
+    }
+}
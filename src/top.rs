@@ -0,0 +1,243 @@
+use std::fmt::Write;
+
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::tree::Tree;
+use crate::AnyError;
+
+#[derive(Copy, Clone, Debug)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+const REPORT_FORMATS: [ReportFormat; 3] =
+    [ReportFormat::Table, ReportFormat::Csv, ReportFormat::Json];
+
+impl ValueEnum for ReportFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &REPORT_FORMATS
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            ReportFormat::Table => Some(PossibleValue::new("table")),
+            ReportFormat::Csv => Some(PossibleValue::new("csv")),
+            ReportFormat::Json => Some(PossibleValue::new("json")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Entry {
+    pub name: String,
+    pub size: i64,
+    /// of the size of the whole tree, from 0 to 100
+    pub percentage: f64,
+}
+
+/// The biggest leaves and folders of a tree, to get numbers instead of a map, e.g. in a CI job.
+#[derive(Debug, Serialize)]
+pub struct TopReport {
+    pub units: String,
+    pub total: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<usize>,
+    pub leaves: Vec<Entry>,
+    pub folders: Vec<Entry>,
+}
+
+impl TopReport {
+    /// Keeps the `top` biggest leaves and folders, not counting the root. If there's a `depth`,
+    /// the folders at that depth are considered leaves, with the size of all their contents. The
+    /// root is at depth 0.
+    pub fn new(tree: &Tree, units: &str, top: usize, depth: Option<usize>) -> Self {
+        let total = tree.size();
+        let mut leaves = Vec::new();
+        let mut folders = Vec::new();
+        for child in &tree.children {
+            collect_entries(child, 1, depth, total, &mut leaves, &mut folders);
+        }
+        Self {
+            units: units.to_string(),
+            total,
+            depth,
+            leaves: biggest(leaves, top),
+            folders: biggest(folders, top),
+        }
+    }
+
+    pub fn format(&self, format: ReportFormat) -> Result<String, AnyError> {
+        Ok(match format {
+            ReportFormat::Table => self.to_table(),
+            ReportFormat::Csv => self.to_csv(),
+            ReportFormat::Json => serde_json::to_string_pretty(self)? + "\n",
+        })
+    }
+
+    pub fn to_table(&self) -> String {
+        let mut table = String::new();
+        let aggregation = match self.depth {
+            Some(depth) => format!(", aggregated to depth {}", depth),
+            None => String::new(),
+        };
+        writeln!(table, "Total: {} {}{}", self.total, self.units, aggregation).unwrap();
+        for (title, entries) in [("leaves", &self.leaves), ("folders", &self.folders)] {
+            let size_width = entries
+                .iter()
+                .map(|entry| entry.size.to_string().len())
+                .max()
+                .unwrap_or(0);
+            writeln!(table, "\nBiggest {} ({}):", title, self.units).unwrap();
+            for Entry {
+                name,
+                size,
+                percentage,
+            } in entries
+            {
+                writeln!(table, "{:>size_width$} {:>6.2}% {}", size, percentage, name).unwrap();
+            }
+        }
+        table
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = "kind,name,size,percentage\n".to_string();
+        for (kind, entries) in [("leaf", &self.leaves), ("folder", &self.folders)] {
+            for entry in entries {
+                writeln!(
+                    csv,
+                    "{},{},{},{:.2}",
+                    kind,
+                    csv_field(&entry.name),
+                    entry.size,
+                    entry.percentage
+                )
+                .unwrap();
+            }
+        }
+        csv
+    }
+}
+
+fn collect_entries(
+    node: &Tree,
+    node_depth: usize,
+    max_depth: Option<usize>,
+    total: i64,
+    leaves: &mut Vec<Entry>,
+    folders: &mut Vec<Entry>,
+) {
+    let entry = Entry {
+        name: node.name.clone(),
+        size: node.size(),
+        percentage: percentage(node.size(), total),
+    };
+    if node.children.is_empty() || max_depth.is_some_and(|max_depth| node_depth >= max_depth) {
+        leaves.push(entry);
+    } else {
+        folders.push(entry);
+        for child in &node.children {
+            collect_entries(child, node_depth + 1, max_depth, total, leaves, folders);
+        }
+    }
+}
+
+fn biggest(mut entries: Vec<Entry>, top: usize) -> Vec<Entry> {
+    entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    entries.truncate(top);
+    entries
+}
+
+fn percentage(size: i64, total: i64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        size as f64 * 100.0 / total as f64
+    }
+}
+
+/// Quotes the field if it has characters that would break the CSV.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> Tree {
+        #[rustfmt::skip]
+        let tree = Tree::new_from_children(".".into(), vec![
+            Tree::new_from_children("./src".into(), vec![
+                Tree::new_from_size("./src/main.rs".into(), 50),
+                Tree::new_from_children("./src/ui".into(), vec![
+                    Tree::new_from_size("./src/ui/map.rs".into(), 20),
+                    Tree::new_from_size("./src/ui/a,b.rs".into(), 10),
+                ]),
+            ]),
+            Tree::new_from_size("./README.md".into(), 20),
+        ]);
+        tree
+    }
+
+    fn names(entries: &[Entry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_top() {
+        let report = TopReport::new(&tree(), "lines", 3, None);
+
+        assert_eq!(report.total, 100);
+        assert_eq!(
+            names(&report.leaves),
+            vec!["./src/main.rs", "./README.md", "./src/ui/map.rs"]
+        );
+        assert_eq!(names(&report.folders), vec!["./src", "./src/ui"]);
+        assert_eq!(report.leaves[0].percentage, 50.0);
+    }
+
+    #[test]
+    fn test_depth() {
+        let report = TopReport::new(&tree(), "lines", 10, Some(2));
+
+        assert_eq!(
+            names(&report.leaves),
+            vec!["./src/main.rs", "./src/ui", "./README.md"]
+        );
+        assert_eq!(names(&report.folders), vec!["./src"]);
+    }
+
+    #[test]
+    fn test_formats() {
+        let report = TopReport::new(&tree(), "lines", 1, None);
+
+        assert_eq!(
+            report.to_table(),
+            "Total: 100 lines\n\
+            \n\
+            Biggest leaves (lines):\n\
+            50  50.00% ./src/main.rs\n\
+            \n\
+            Biggest folders (lines):\n\
+            80  80.00% ./src\n"
+        );
+        assert_eq!(
+            report.to_csv(),
+            "kind,name,size,percentage\n\
+            leaf,./src/main.rs,50,50.00\n\
+            folder,./src,80,80.00\n"
+        );
+        let json = report.format(ReportFormat::Json).unwrap();
+        assert!(json.contains(r#""name": "./src/main.rs""#));
+        assert_eq!(csv_field("./src/ui/a,b.rs"), r#""./src/ui/a,b.rs""#);
+    }
+}
@@ -1,12 +1,17 @@
+use std::collections::HashMap;
+
 use macroquad::prelude::{Rect, Vec2};
+use serde::{Deserialize, Serialize};
 
 use crate::arrangements::binary::squareness;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tree {
     pub name: String,
     pub size: Option<i64>,
+    #[serde(skip)]
     pub rect: Option<Rect>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Tree>,
 }
 
@@ -106,6 +111,16 @@ impl Tree {
         }
     }
 
+    pub fn get_by_name_mut(&mut self, name: &str) -> Option<&mut Tree> {
+        if self.name == name {
+            Some(self)
+        } else {
+            self.children
+                .iter_mut()
+                .find_map(|child| child.get_by_name_mut(name))
+        }
+    }
+
     /// Returns the count of leaf nodes (e.g. actual files in bytes-per-file) and total nodes (files + folders)
     pub fn count(&self) -> Counts {
         self.count_if(&|_| true)
@@ -224,6 +239,58 @@ impl Tree {
     pub fn size(&self) -> i64 {
         self.size.unwrap()
     }
+
+    /// Sets the rect of each node to the rect of the node with the same name in the other tree, or
+    /// to None if there's no such node. Useful as hints for the stable arrangement.
+    pub fn copy_rects_from(&mut self, other: &Tree) {
+        let mut rects = HashMap::new();
+        other.collect_rects(&mut rects);
+        self.set_rects(&rects);
+    }
+    fn collect_rects(&self, rects: &mut HashMap<String, Rect>) {
+        if let Some(rect) = self.rect {
+            rects.insert(self.name.clone(), rect);
+        }
+        for child in &self.children {
+            child.collect_rects(rects);
+        }
+    }
+    fn set_rects(&mut self, rects: &HashMap<String, Rect>) {
+        self.rect = rects.get(&self.name).copied();
+        for child in &mut self.children {
+            child.set_rects(rects);
+        }
+    }
+
+    /// Returns the size of each leaf, keyed by its path relative to the root of this tree, so that
+    /// trees with different naming schemes (e.g. `./src/main.rs` and `src/main.rs`) can be matched.
+    pub fn leaf_sizes_by_path(&self) -> HashMap<String, i64> {
+        let mut sizes = HashMap::new();
+        self.leaf_sizes_by_path_recursive(&self.name, &mut sizes);
+        sizes
+    }
+    fn leaf_sizes_by_path_recursive(&self, root: &str, sizes: &mut HashMap<String, i64>) {
+        if self.is_leaf() {
+            sizes.insert(relative_path(root, &self.name), self.size());
+        } else {
+            for child in &self.children {
+                child.leaf_sizes_by_path_recursive(root, sizes);
+            }
+        }
+    }
+}
+
+/// Strips the root folder and any leading "./" or "/" from the name of a node.
+pub fn relative_path(root: &str, name: &str) -> String {
+    let mut relative = name.strip_prefix(root).unwrap_or(name);
+    loop {
+        relative = relative.trim_start_matches('/');
+        if let Some(stripped) = relative.strip_prefix("./") {
+            relative = stripped;
+        } else {
+            return relative.to_string();
+        }
+    }
 }
 
 impl PartialEq for Tree {
@@ -299,6 +366,15 @@ mod tests {
         assert_eq!(squareness, 0.0);
     }
 
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(relative_path(".", "./src/main.rs"), "src/main.rs");
+        assert_eq!(relative_path("./", "./src/main.rs"), "src/main.rs");
+        assert_eq!(relative_path("./", ".//src/main.rs"), "src/main.rs");
+        assert_eq!(relative_path("code", "code/src/main.rs"), "src/main.rs");
+        assert_eq!(relative_path("", "src/main.rs"), "src/main.rs");
+    }
+
     #[test]
     fn test_fuzzy_search() {
         assert!(fuzzy_contains("hello world", &vec!["hello", "world"]));
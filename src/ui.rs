@@ -1,22 +1,32 @@
 use clipboard_rs::{Clipboard, ClipboardContext};
 use macroquad::math::f32;
 use macroquad::prelude::{
-    clear_background, is_mouse_button_pressed, mouse_position, screen_height, screen_width,
-    MouseButton, Rect, Vec2, LIGHTGRAY,
+    clear_background, is_key_pressed, is_mouse_button_pressed, mouse_position, screen_height,
+    screen_width, KeyCode, MouseButton, Rect, Vec2, LIGHTGRAY,
 };
 
+use crate::arrangements::Arrangement;
+use crate::heatmap::Heatmap;
 use crate::tree::{Tree, TreeView};
 use crate::ui::buttons::draw_buttons;
+use crate::ui::heatmap_view::draw_legend;
+use crate::ui::labels::{draw_labels, fit_labels, reserve_headers, Label};
 use crate::ui::map_and_path::choose_and_draw_map_and_path;
 use crate::ui::rect_utils::round_rect;
 use crate::ui::searcher::Searcher;
+use crate::ui::zoom::{draw_breadcrumb, zoomed_root, zoomed_root_mut, DoubleClick};
 
 mod buttons;
+mod heatmap_view;
 mod input_text;
 mod key_queue;
-mod map_and_path;
+mod labels;
+pub(crate) mod map_and_path;
+pub mod progress_view;
 pub mod rect_utils;
 pub mod searcher;
+pub mod timeline_view;
+mod zoom;
 
 const FONT_SIZE: f32 = 16.0;
 
@@ -29,22 +39,23 @@ pub struct Ui {
     selected: Option<Vec<TreeView>>,
     level: Option<usize>,
     keys: key_queue::OrderedEventHandler,
-    arrange: fn(f32, String, &mut Tree, Rect),
-    arrangement: String,
+    arrangement: Box<dyn Arrangement>,
+    /// draw the names of the files and folders inside their rects
+    labels: bool,
+    /// the names that fit in the current rects, if `labels`
+    fitted_labels: Vec<Label>,
+    /// colors of the leaves from a second metric
+    heatmap: Option<Heatmap>,
     width: f32,
     height: f32,
-    padding: f32,
     refresh: bool,
+    /// name of the node that is drawn as if it was the root. None shows the whole tree
+    zoomed: Option<String>,
+    double_click: DoubleClick,
 }
 
 impl Ui {
-    pub fn new(
-        tree: Tree,
-        units: &str,
-        arrange: fn(f32, String, &mut Tree, Rect),
-        arrangement: String,
-        padding: f32,
-    ) -> Self {
+    pub fn new(tree: Tree, units: &str, arrangement: Box<dyn Arrangement>, labels: bool) -> Self {
         let width = screen_width();
         let height = screen_height();
         let font_size = choose_font_size(width, height);
@@ -60,12 +71,15 @@ impl Ui {
             selected: None,
             level: None,
             keys: key_queue::OrderedEventHandler::new(),
-            arrange,
             width,
             height,
-            padding,
             arrangement,
+            labels,
+            fitted_labels: Vec::new(),
+            heatmap: None,
             refresh: false,
+            zoomed: None,
+            double_click: DoubleClick::default(),
         }
     }
 
@@ -75,22 +89,129 @@ impl Ui {
 
         clear_background(LIGHTGRAY);
 
+        let root = zoomed_root(&self.tree, &self.zoomed);
         choose_and_draw_map_and_path(
-            &self.tree,
+            root,
             &self.units,
             self.map_rect,
             self.font_size,
             &mut self.searcher,
             &mut self.selected,
             &mut self.level,
+            self.heatmap.as_ref(),
         );
 
-        select_node_with_mouse(&self.tree, self.map_rect, &mut self.selected);
+        if self.labels {
+            draw_labels(&self.fitted_labels);
+        }
+
+        let double_clicked = select_node_with_mouse(
+            root,
+            self.map_rect,
+            &mut self.selected,
+            &mut self.double_click,
+        );
+        let zoom_out_to = self
+            .zoomed
+            .as_ref()
+            .and_then(|zoomed| draw_breadcrumb(&self.tree, zoomed, self.map_rect, self.font_size));
+        let zoom_key_pressed = !self.searcher.is_focused() && is_key_pressed(KeyCode::Z);
+        let zoom_out_pressed = !self.searcher.is_focused() && is_key_pressed(KeyCode::Backspace);
 
         self.searcher
-            .draw_search(&self.tree, &self.keys.keycode_event_queue);
+            .draw_search(root, &self.keys.keycode_event_queue);
 
         self.act_on_buttons();
+        if let Some(heatmap) = &self.heatmap {
+            draw_legend(heatmap, self.map_rect, self.font_size);
+        }
+
+        if let Some(double_clicked) = double_clicked {
+            self.zoom_in(double_clicked);
+        } else if zoom_key_pressed {
+            if let Some(selected) = self.selected_to_zoom() {
+                self.zoom_in(selected);
+            }
+        } else if let Some(ancestor) = zoom_out_to {
+            self.zoom_in(ancestor);
+        } else if zoom_out_pressed {
+            self.zoom_out();
+        }
+    }
+
+    pub fn set_heatmap(&mut self, heatmap: Heatmap) {
+        self.heatmap = Some(heatmap);
+    }
+
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    /// Shows another tree (e.g. another commit of a timeline), keeping the zoom if the zoomed node
+    /// still exists. The rects of the current tree are kept as hints if the arrangement uses them.
+    pub fn replace_tree(&mut self, mut tree: Tree) {
+        if self.arrangement.uses_previous_rects() {
+            tree.copy_rects_from(&self.tree);
+        }
+        self.tree = tree;
+        if let Some(zoomed) = &self.zoomed {
+            if self.tree.get_nested_by_name(zoomed).is_empty() {
+                self.zoomed = None;
+            }
+        }
+        self.selected = None;
+        self.level = None;
+        self.arrange();
+    }
+
+    /// Keeps zooming into the same node after a refresh, if it still exists.
+    pub fn zoom_like(&mut self, previous: &Ui) {
+        if let Some(zoomed) = &previous.zoomed {
+            if !self.tree.get_nested_by_name(zoomed).is_empty() {
+                self.zoom_in(zoomed.clone());
+            }
+        }
+    }
+
+    /// The node of the selected level in the path, or the deepest selected folder.
+    fn selected_to_zoom(&self) -> Option<String> {
+        let selected = self.selected.as_ref()?;
+        let node = match self.level {
+            Some(level) => selected.get(level)?,
+            None => selected.iter().rev().find(|node| node.children_count > 0)?,
+        };
+        Some(node.name.clone())
+    }
+
+    fn zoom_in(&mut self, name: String) {
+        self.zoomed = if name == self.tree.name {
+            None
+        } else {
+            Some(name)
+        };
+        self.selected = None;
+        self.level = None;
+        self.arrange();
+    }
+
+    fn zoom_out(&mut self) {
+        if let Some(zoomed) = &self.zoomed {
+            let nested = self.tree.get_nested_by_name(zoomed);
+            if nested.len() >= 2 {
+                let parent = nested[nested.len() - 2].name.clone();
+                self.zoom_in(parent);
+            }
+        }
+    }
+
+    /// Arranges the zoomed node (or the whole tree, if not zoomed) in the map.
+    pub fn arrange(&mut self) {
+        let root = zoomed_root_mut(&mut self.tree, &self.zoomed);
+        self.arrangement.arrange(root, self.map_rect);
+        if self.labels {
+            reserve_headers(root, self.font_size);
+            self.fitted_labels = fit_labels(root, self.font_size);
+        }
     }
 
     fn maybe_rearrange(&mut self) {
@@ -101,12 +222,7 @@ impl Ui {
             self.width = new_width;
             self.height = new_height;
             self.map_rect = get_map_rect(self.width, self.height, self.font_size);
-            (self.arrange)(
-                self.padding,
-                self.arrangement.clone(),
-                &mut self.tree,
-                self.map_rect,
-            );
+            self.arrange();
             self.searcher
                 .position(get_searcher_rect(self.map_rect, self.font_size));
         }
@@ -166,17 +282,34 @@ fn choose_font_size(width: f32, height: f32) -> f32 {
         }
 }
 
-fn select_node_with_mouse(tree: &Tree, map_rect: Rect, selected: &mut Option<Vec<TreeView>>) {
+/// Returns the name of the deepest folder that was double-clicked, if any, to zoom into it.
+fn select_node_with_mouse(
+    tree: &Tree,
+    map_rect: Rect,
+    selected: &mut Option<Vec<TreeView>>,
+    double_click: &mut DoubleClick,
+) -> Option<String> {
     let mouse_position = Vec2::from(mouse_position());
     if map_rect.contains(mouse_position) {
         if is_mouse_button_pressed(MouseButton::Left) {
             let nodes_pointed = tree.get_nested_by_position(mouse_position);
+            if double_click.is_double_click() {
+                let deepest_folder = nodes_pointed
+                    .iter()
+                    .skip(1)
+                    .rev()
+                    .find(|node| !node.children.is_empty());
+                if let Some(folder) = deepest_folder {
+                    return Some(folder.name.clone());
+                }
+            }
             let new_nodes = TreeView::from_nodes(&nodes_pointed);
             set_if_different_or_unset_if_same(selected, new_nodes);
         } else if is_mouse_button_pressed(MouseButton::Right) {
             *selected = None;
         }
     }
+    None
 }
 
 fn set_if_different_or_unset_if_same<T: PartialEq>(selected: &mut Option<T>, new_nodes: T) {
@@ -29,12 +29,11 @@ pub fn draw_buttons(map_rect: Rect, font_size: f32) -> PressedButtons {
     }
 }
 
-fn draw_button(text: &str, x: f32, y: f32, font_size: f32) -> (Rect, bool) {
+pub(crate) fn draw_button(text: &str, x: f32, y: f32, font_size: f32) -> (Rect, bool) {
     let horizontal_pad = font_size * 1.0;
     let mut pressed = false;
 
-    let measure = measure_text(text, None, font_size as u16, 1.0);
-    let button_rect = Rect::new(x, y, measure.width + horizontal_pad * 2.0, font_size * 1.5);
+    let button_rect = Rect::new(x, y, button_width(text, font_size), font_size * 1.5);
     if button_rect.contains(Vec2::from(mouse_position())) {
         if is_mouse_button_pressed(MouseButton::Left) {
             pressed = true;
@@ -56,3 +55,8 @@ fn draw_button(text: &str, x: f32, y: f32, font_size: f32) -> (Rect, bool) {
     );
     (button_rect, pressed)
 }
+
+pub(crate) fn button_width(text: &str, font_size: f32) -> f32 {
+    let horizontal_pad = font_size * 1.0;
+    measure_text(text, None, font_size as u16, 1.0).width + horizontal_pad * 2.0
+}
@@ -0,0 +1,83 @@
+use macroquad::color::{Color, BLACK, DARKGRAY};
+use macroquad::math::Rect;
+use macroquad::math::Vec2;
+use macroquad::prelude::{
+    draw_line, draw_rectangle_lines, draw_text, draw_triangle, measure_text, screen_height,
+};
+
+use crate::heatmap::{Heatmap, Mark};
+use crate::metrics::{format_number, format_units};
+use crate::tree::Tree;
+use crate::ui::rect_utils::{draw_rect, round_rect};
+
+const LEGEND_STEPS: usize = 32;
+const ADDED_COLOR: Color = Color::new(0.1, 0.45, 0.2, 1.0);
+/// The biggest side of the corner that marks an added file.
+const MAX_MARK_SIZE: f32 = 12.0;
+
+/// Fills each leaf with the color of its value in the heatmap. Leaves without a value are left
+/// with the background color. Added leaves get a corner, and removed leaves are crossed out.
+pub fn draw_heatmap(node: &Tree, heatmap: &Heatmap) {
+    let Some(rect) = node.rect else {
+        return;
+    };
+    if node.children.is_empty() {
+        let rect = round_rect(rect);
+        if let Some(color) = heatmap.color(&node.name) {
+            draw_rect(rect, color);
+        }
+        match heatmap.mark(&node.name) {
+            Some(Mark::Added) => {
+                let size = (rect.w.min(rect.h) * 0.5).min(MAX_MARK_SIZE);
+                let right = rect.x + rect.w;
+                draw_triangle(
+                    Vec2::new(right - size, rect.y),
+                    Vec2::new(right, rect.y),
+                    Vec2::new(right, rect.y + size),
+                    ADDED_COLOR,
+                );
+            }
+            Some(Mark::Removed) => {
+                let (right, bottom) = (rect.x + rect.w, rect.y + rect.h);
+                draw_line(rect.x, rect.y, right, bottom, 1.0, DARKGRAY);
+                draw_line(rect.x, bottom, right, rect.y, 1.0, DARKGRAY);
+            }
+            None => {}
+        }
+    } else {
+        for child in &node.children {
+            draw_heatmap(child, heatmap);
+        }
+    }
+}
+
+/// Draws the gradient with the lowest and highest values, aligned to the right of the map, at the
+/// height of the buttons.
+pub fn draw_legend(heatmap: &Heatmap, map_rect: Rect, font_size: f32) {
+    let bar_width = font_size * 12.0;
+    let bar_height = font_size * 0.75;
+    let y = screen_height() - font_size * 3.5;
+    let min_text = &format_number(heatmap.min());
+    let max_text = format_units(heatmap.max, &heatmap.units);
+    let title = "Color (log scale):";
+    let measure = |text: &str| measure_text(text, None, font_size as u16, 1.0).width;
+    let pad = font_size * 0.5;
+
+    let max_x = map_rect.x + map_rect.w - measure(&max_text);
+    let bar_x = max_x - pad - bar_width;
+    let min_x = bar_x - pad - measure(min_text);
+    let title_x = min_x - pad - measure(title);
+    let text_y = y + font_size;
+    draw_text(title, title_x, text_y, font_size, BLACK);
+    draw_text(min_text, min_x, text_y, font_size, BLACK);
+    draw_text(&max_text, max_x, text_y, font_size, BLACK);
+
+    let bar_y = y + (font_size * 1.5 - bar_height) * 0.5;
+    let step_width = bar_width / LEGEND_STEPS as f32;
+    for i in 0..LEGEND_STEPS {
+        let fraction = i as f32 / (LEGEND_STEPS - 1) as f32;
+        let step = Rect::new(bar_x + i as f32 * step_width, bar_y, step_width, bar_height);
+        draw_rect(round_rect(step), heatmap.legend_color(fraction));
+    }
+    draw_rectangle_lines(bar_x, bar_y, bar_width, bar_height, 1.0, DARKGRAY);
+}
@@ -0,0 +1,160 @@
+use macroquad::color::{Color, BLACK};
+use macroquad::math::Rect;
+use macroquad::prelude::{draw_text, measure_text};
+
+use crate::arrangements::remap_children;
+use crate::tree::Tree;
+
+const ELLIPSIS: &str = "...";
+/// Labels smaller than this are not readable, so they are not drawn.
+const MIN_LABEL_FONT_SIZE: f32 = 9.0;
+const HEADER_COLOR: Color = Color::new(0.2, 0.2, 0.2, 1.0);
+
+/// Moves the children of each folder down, leaving a strip at the top of the folder for its name,
+/// as in classic disk-usage treemaps. Only folders big enough get a header. The root doesn't get
+/// one because its name is already in the path below the map.
+pub fn reserve_headers(tree: &mut Tree, font_size: f32) {
+    let root_name = tree.name.clone();
+    remap_children(tree, &|node, rect| {
+        if node.name != root_name && has_header(rect, font_size) {
+            let header_height = header_height(font_size);
+            Rect::new(
+                rect.x,
+                rect.y + header_height,
+                rect.w,
+                rect.h - header_height,
+            )
+        } else {
+            rect
+        }
+    });
+}
+
+/// A name that fits in its rect. The labels are computed when the rects change, so that drawing
+/// them each frame doesn't need to measure the text again.
+pub struct Label {
+    text: String,
+    x: f32,
+    y: f32,
+    font_size: f32,
+    color: Color,
+}
+
+/// Computes the name of the folders in their headers, and the name of the files inside their
+/// rects if they are big enough. Long names are truncated with an ellipsis.
+pub fn fit_labels(tree: &Tree, font_size: f32) -> Vec<Label> {
+    let mut labels = Vec::new();
+    for child in &tree.children {
+        fit_labels_recursive(child, font_size, &mut labels);
+    }
+    labels
+}
+
+pub fn draw_labels(labels: &[Label]) {
+    for label in labels {
+        draw_text(&label.text, label.x, label.y, label.font_size, label.color);
+    }
+}
+
+fn fit_labels_recursive(node: &Tree, font_size: f32, labels: &mut Vec<Label>) {
+    let Some(rect) = node.rect else {
+        return;
+    };
+    if node.children.is_empty() {
+        let label_font_size = font_size.min(rect.h * 0.7);
+        labels.extend(fit_label(node, rect, label_font_size, BLACK));
+    } else {
+        if has_header(rect, font_size) {
+            labels.extend(fit_label(node, rect, font_size, HEADER_COLOR));
+        }
+        for child in &node.children {
+            fit_labels_recursive(child, font_size, labels);
+        }
+    }
+}
+
+fn fit_label(node: &Tree, rect: Rect, font_size: f32, color: Color) -> Option<Label> {
+    if font_size < MIN_LABEL_FONT_SIZE {
+        return None;
+    }
+    let pad = font_size * 0.25;
+    let measure = |text: &str| measure_text(text, None, font_size as u16, 1.0).width;
+    let text = fit_text(basename(&node.name), rect.w - 2.0 * pad, measure)?;
+    Some(Label {
+        text,
+        x: (rect.x + pad).round(),
+        y: (rect.y + pad + font_size * 0.75).round(),
+        font_size,
+        color,
+    })
+}
+
+fn has_header(rect: Rect, font_size: f32) -> bool {
+    rect.h >= 3.0 * header_height(font_size) && rect.w >= 4.0 * font_size
+}
+
+fn header_height(font_size: f32) -> f32 {
+    font_size * 1.25
+}
+
+fn basename(name: &str) -> &str {
+    let trimmed = name.trim_end_matches('/');
+    trimmed.rsplit('/').next().unwrap_or(trimmed)
+}
+
+/// Returns the text, or its beginning followed by an ellipsis, so that its width is at most
+/// `max_width`. Returns None if not even one character and the ellipsis fit.
+pub(crate) fn fit_text(
+    text: &str,
+    max_width: f32,
+    measure: impl Fn(&str) -> f32,
+) -> Option<String> {
+    if measure(text) <= max_width {
+        return Some(text.to_string());
+    }
+    let char_ends = text.char_indices().map(|(i, c)| i + c.len_utf8());
+    let mut fitting = None;
+    for end in char_ends {
+        let candidate = format!("{}{}", &text[..end], ELLIPSIS);
+        if measure(&candidate) <= max_width {
+            fitting = Some(candidate);
+        } else {
+            break;
+        }
+    }
+    fitting
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn char_count(text: &str) -> f32 {
+        text.chars().count() as f32
+    }
+
+    #[test]
+    fn test_basename() {
+        assert_eq!(basename("./src/ui/labels.rs"), "labels.rs");
+        assert_eq!(basename("./src/"), "src");
+        assert_eq!(basename("."), ".");
+        assert_eq!(basename("word"), "word");
+    }
+
+    #[test]
+    fn test_fit_text() {
+        assert_eq!(
+            fit_text("main.rs", 7.0, char_count),
+            Some("main.rs".to_string())
+        );
+        assert_eq!(
+            fit_text("main.rs", 6.0, char_count),
+            Some("mai...".to_string())
+        );
+        assert_eq!(
+            fit_text("main.rs", 4.0, char_count),
+            Some("m...".to_string())
+        );
+        assert_eq!(fit_text("main.rs", 3.0, char_count), None);
+    }
+}
@@ -1,4 +1,7 @@
+use crate::heatmap::Heatmap;
+use crate::metrics::format_units;
 use crate::tree::{Tree, TreeView};
+use crate::ui::heatmap_view::draw_heatmap;
 use crate::ui::rect_utils::{draw_rect, is_rect_clicked, round_rect};
 use crate::ui::searcher::Searcher;
 use crate::ui::set_if_different_or_unset_if_same;
@@ -12,7 +15,7 @@ use macroquad::prelude::{
 use std::collections::VecDeque;
 
 // HSV 238, 35, 98, rotating 47 Hue units https://supercolorpalette.com
-const COLORS_HEX: &[u32] = &[
+pub(crate) const COLORS_HEX: &[u32] = &[
     0xE1A2FA, 0xFAA2CE, 0xFABAA2, 0xF6FAA2, 0xB1FAA2, 0xA2FAD8, 0xA2D7FA, 0xB2A2FA, 0xF7A2FA,
     0xFAA2B8, 0xFAD0A2, 0xE0FAA2, 0xA2FAAA, 0xA2FAEE, 0xA2C1FA, 0xC8A2FA, 0xFAA2E7, 0xFAA2A2,
     0xFAE5A2, 0xCAFAA2,
@@ -34,6 +37,7 @@ const fn from_hex(hex: u32) -> Color {
     color_u8!(hex / 0x10000, hex / 0x100 % 0x100, hex % 0x100, 255)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn choose_and_draw_map_and_path(
     tree: &Tree,
     units: &str,
@@ -42,14 +46,34 @@ pub fn choose_and_draw_map_and_path(
     searcher: &mut Searcher,
     selected: &mut Option<Vec<TreeView>>,
     level: &mut Option<usize>,
+    heatmap: Option<&Heatmap>,
 ) {
+    // with a heatmap, the selection is drawn as outlines so that the colors of the leaves are visible
+    let outline_only = heatmap.is_some();
+    if let Some(heatmap) = heatmap {
+        draw_heatmap(tree, heatmap);
+    }
     if let Some(nested_nodes) = searcher.get_new_result() {
         *selected = Some(nested_nodes.clone());
-        draw_colored_map_and_path(units, map_rect, font_size, &nested_nodes, level);
+        draw_colored_map_and_path(
+            units,
+            map_rect,
+            font_size,
+            &nested_nodes,
+            level,
+            outline_only,
+        );
     } else if let Some(selected_nodes) = &selected {
-        draw_colored_map_and_path(units, map_rect, font_size, &selected_nodes, level);
+        draw_colored_map_and_path(
+            units,
+            map_rect,
+            font_size,
+            &selected_nodes,
+            level,
+            outline_only,
+        );
     } else {
-        draw_hovered_nested_nodes(units, &tree, map_rect, font_size, level);
+        draw_hovered_nested_nodes(units, &tree, map_rect, font_size, level, outline_only);
     }
 
     draw_nodes_lines(&tree, map_rect, *level, font_size);
@@ -61,10 +85,11 @@ fn draw_colored_map_and_path(
     font_size: f32,
     nested_nodes: &Vec<TreeView>,
     level_opt: &mut Option<usize>,
+    outline_only: bool,
 ) {
     if nested_nodes.len() > 0 {
         draw_path(units, map_rect, font_size, nested_nodes, level_opt);
-        draw_colored_selected_in_map(nested_nodes, level_opt);
+        draw_colored_selected_in_map(nested_nodes, level_opt, outline_only);
     }
 }
 
@@ -218,28 +243,15 @@ fn draw_path_text(
     );
 }
 
-fn format_units(value: i64, units: &str) -> String {
-    let separator = " ";
-    let space_separated_number = value
-        .to_string()
-        .as_bytes()
-        .rchunks(3)
-        .rev()
-        .collect::<Vec<&[u8]>>()
-        .join(separator.as_bytes());
-
-    format!(
-        "{} {}",
-        String::from_utf8(space_separated_number).unwrap(),
-        units
-    )
-}
-
-fn draw_colored_selected_in_map(nested_nodes: &Vec<TreeView>, level_opt: &mut Option<usize>) {
+fn draw_colored_selected_in_map(
+    nested_nodes: &Vec<TreeView>,
+    level_opt: &mut Option<usize>,
+    outline_only: bool,
+) {
     for (i, node) in nested_nodes.iter().enumerate() {
         if let Some(node_rect) = node.rect {
             let Rect { x, y, w, h } = round_rect(node_rect);
-            if level_opt.is_some_and(|level| i > level) {
+            if outline_only || level_opt.is_some_and(|level| i > level) {
                 let thickness = w.min(h).min(10.0);
                 draw_rectangle_lines(x, y, w, h, thickness, COLORS[i % COLORS.len()]);
             } else {
@@ -257,6 +269,7 @@ fn draw_hovered_nested_nodes(
     map_rect: Rect,
     font_size: f32,
     level: &mut Option<usize>,
+    outline_only: bool,
 ) {
     let mouse_position = Vec2::from(mouse_position());
     if map_rect.contains(mouse_position) {
@@ -267,6 +280,7 @@ fn draw_hovered_nested_nodes(
             font_size,
             &TreeView::from_nodes(&nodes_pointed),
             level,
+            outline_only,
         );
     }
 }
@@ -297,20 +311,7 @@ fn draw_nodes_lines_recursive(
     current_level: usize,
 ) {
     if let Some(rect) = node.rect {
-        // draw_text(
-        //     &node.name,
-        //     x + 1.5 * font_size,
-        //     y + 1.5 * font_size,
-        //     font_size,
-        //     BLACK,
-        // );
-        // draw_text(
-        //     &node.size.to_string(),
-        //     x + 1.5 * font_size,
-        //     y + 3.0 * font_size,
-        //     font_size,
-        //     BLACK,
-        // );
+        // the labels are drawn in ui::labels
         for child in &node.children {
             draw_nodes_lines_recursive(
                 child,
@@ -0,0 +1,64 @@
+use macroquad::color::{Color, BLACK, DARKGRAY, WHITE};
+use macroquad::math::Rect;
+use macroquad::prelude::{
+    draw_rectangle, draw_rectangle_lines, draw_text, measure_text, screen_height, screen_width,
+};
+
+use crate::progress::Progress;
+use crate::ui::choose_font_size;
+use crate::ui::rect_utils::{draw_rect, round_rect};
+
+const VEIL: Color = Color::new(0.0, 0.0, 0.0, 0.3);
+
+/// Draws a box in the middle of the screen with how far the computation of the metric got, on top
+/// of whatever was drawn before (e.g. the map from before a refresh).
+pub fn draw_progress(progress: &Progress, description: &str) {
+    let width = screen_width();
+    let height = screen_height();
+    let font_size = choose_font_size(width, height);
+
+    let mut lines = vec![description.to_string()];
+    let total_commits = progress.total_commits();
+    if total_commits > 0 {
+        lines.push(format!(
+            "Commits processed: {} of {}",
+            progress.commits(),
+            total_commits
+        ));
+    }
+    let files = progress.files();
+    if files > 0 {
+        lines.push(format!("Files scanned: {}", files));
+    }
+    lines.push("Press Escape to cancel".to_string());
+
+    let pad = font_size;
+    let line_height = font_size * 1.5;
+    let text_width = lines
+        .iter()
+        .map(|line| measure_text(line, None, font_size as u16, 1.0).width)
+        .fold(0.0, f32::max);
+    let box_width = text_width + 2.0 * pad;
+    let box_height = lines.len() as f32 * line_height + 2.0 * pad;
+    let box_rect = round_rect(Rect::new(
+        (width - box_width) * 0.5,
+        (height - box_height) * 0.5,
+        box_width,
+        box_height,
+    ));
+
+    draw_rectangle(0.0, 0.0, width, height, VEIL);
+    draw_rect(box_rect, WHITE);
+    draw_rectangle_lines(
+        box_rect.x, box_rect.y, box_rect.w, box_rect.h, 2.0, DARKGRAY,
+    );
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(
+            line,
+            box_rect.x + pad,
+            box_rect.y + pad + font_size + i as f32 * line_height,
+            font_size,
+            BLACK,
+        );
+    }
+}
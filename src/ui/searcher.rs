@@ -48,6 +48,9 @@ impl Searcher {
         rect.y -= self.font_size;
         self.rect = rect;
     }
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
     pub fn get_new_result(&mut self) -> Option<&Vec<TreeView>> {
         if self.result_changed {
             self.result_changed = false;
@@ -0,0 +1,119 @@
+use macroquad::color::{BLACK, DARKGRAY, GRAY};
+use macroquad::input::mouse_position;
+use macroquad::math::{Rect, Vec2};
+use macroquad::prelude::{
+    draw_line, draw_text, get_time, is_mouse_button_down, is_mouse_button_pressed, measure_text,
+    screen_height, MouseButton,
+};
+
+use crate::ui::buttons::{button_width, draw_button};
+use crate::ui::labels::fit_text;
+use crate::ui::rect_utils::{draw_rect, round_rect};
+
+const SECONDS_PER_FRAME: f64 = 1.0;
+
+/// A play button and a slider to choose which frame of a timeline is shown.
+#[derive(Default)]
+pub struct TimelineControls {
+    playing: bool,
+    last_step_time: f64,
+    dragging: bool,
+}
+
+impl TimelineControls {
+    /// Draws the controls at the right of the buttons, with the label of the current frame below
+    /// the slider. Returns the frame that should be shown, if it's not `current` anymore.
+    pub fn draw(
+        &mut self,
+        current: usize,
+        count: usize,
+        label: &str,
+        map_rect: Rect,
+        font_size: f32,
+    ) -> Option<usize> {
+        let y = screen_height() - font_size * 3.5;
+        let slider = round_rect(Rect::new(
+            map_rect.x + map_rect.w * 0.5,
+            y,
+            map_rect.w * 0.5,
+            font_size * 1.5,
+        ));
+        let mut next = None;
+
+        let button_text = if self.playing { "Pause" } else { "Play" };
+        let button_x = slider.x - font_size - button_width(button_text, font_size);
+        let (_, play_pressed) = draw_button(button_text, button_x, y, font_size);
+        if play_pressed {
+            self.playing = !self.playing;
+            self.last_step_time = get_time();
+            if self.playing && current + 1 == count {
+                next = Some(0);
+            }
+        }
+
+        let thumb_width = font_size * 0.75;
+        let track_width = slider.w - thumb_width;
+        let mouse = Vec2::from(mouse_position());
+        if is_mouse_button_pressed(MouseButton::Left) && slider.contains(mouse) {
+            self.dragging = true;
+            self.playing = false;
+        } else if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = false;
+        }
+        if self.dragging && count > 1 {
+            let fraction = ((mouse.x - slider.x - thumb_width * 0.5) / track_width).clamp(0.0, 1.0);
+            let index = (fraction * (count - 1) as f32).round() as usize;
+            if index != current {
+                next = Some(index);
+            }
+        }
+
+        if self.playing && get_time() - self.last_step_time >= SECONDS_PER_FRAME {
+            self.last_step_time = get_time();
+            if current + 1 < count {
+                next = Some(current + 1);
+            } else {
+                self.playing = false;
+            }
+        }
+
+        let shown = next.unwrap_or(current);
+        draw_slider(slider, thumb_width, shown, count);
+        let label = format!("{}/{} {}", shown + 1, count, label);
+        let measure = |text: &str| measure_text(text, None, font_size as u16, 1.0).width;
+        if let Some(label) = fit_text(&label, slider.w, measure) {
+            draw_text(&label, slider.x, y + font_size * 2.6, font_size, BLACK);
+        }
+        next
+    }
+}
+
+fn draw_slider(slider: Rect, thumb_width: f32, current: usize, count: usize) {
+    let track_width = slider.w - thumb_width;
+    let middle_y = slider.y + slider.h * 0.5;
+    let track_x = slider.x + thumb_width * 0.5;
+    draw_line(
+        track_x,
+        middle_y,
+        track_x + track_width,
+        middle_y,
+        2.0,
+        DARKGRAY,
+    );
+    let step = if count > 1 {
+        track_width / (count - 1) as f32
+    } else {
+        0.0
+    };
+    for i in 0..count {
+        let x = track_x + i as f32 * step;
+        draw_line(x, middle_y - 3.0, x, middle_y + 3.0, 1.0, DARKGRAY);
+    }
+    let thumb = Rect::new(
+        track_x + current as f32 * step - thumb_width * 0.5,
+        slider.y,
+        thumb_width,
+        slider.h,
+    );
+    draw_rect(round_rect(thumb), GRAY);
+}
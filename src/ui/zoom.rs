@@ -0,0 +1,112 @@
+use macroquad::color::{BLACK, GRAY};
+use macroquad::math::{Rect, Vec2};
+use macroquad::prelude::{draw_text, get_time, measure_text, mouse_position, MouseButton};
+
+use crate::tree::Tree;
+use crate::ui::rect_utils::{draw_rect, is_rect_clicked};
+
+/// Maximum time in seconds between the two clicks of a double-click.
+const DOUBLE_CLICK_SECONDS: f64 = 0.4;
+const BREADCRUMB_SEPARATOR: &str = " > ";
+
+#[derive(Default)]
+pub struct DoubleClick {
+    last_click: Option<(f64, Vec2)>,
+}
+
+impl DoubleClick {
+    /// To be called when the left mouse button is pressed. Returns true if it was pressed
+    /// recently at the same position.
+    pub fn is_double_click(&mut self) -> bool {
+        let now = get_time();
+        let position = Vec2::from(mouse_position());
+        let is_double = self.last_click.is_some_and(|(time, last_position)| {
+            now - time < DOUBLE_CLICK_SECONDS && last_position.distance(position) < 4.0
+        });
+        // a triple click is a double click plus a single click
+        self.last_click = if is_double {
+            None
+        } else {
+            Some((now, position))
+        };
+        is_double
+    }
+}
+
+/// Returns the root if `zoomed` is None or doesn't exist in the tree (e.g. after a refresh).
+pub fn zoomed_root<'a>(tree: &'a Tree, zoomed: &Option<String>) -> &'a Tree {
+    zoomed
+        .as_ref()
+        .and_then(|name| tree.get_nested_by_name(name).last().copied())
+        .unwrap_or(tree)
+}
+
+pub fn zoomed_root_mut<'a>(tree: &'a mut Tree, zoomed: &Option<String>) -> &'a mut Tree {
+    match zoomed {
+        // checking first avoids returning `tree` while it's still borrowed by the search
+        Some(name) if !tree.get_nested_by_name(name).is_empty() => {
+            tree.get_by_name_mut(name).unwrap()
+        }
+        _ => tree,
+    }
+}
+
+/// Draws the nodes from the root to the zoomed node above the map. Returns the name of the node
+/// that was clicked, to zoom out to it.
+pub fn draw_breadcrumb(
+    tree: &Tree,
+    zoomed: &str,
+    map_rect: Rect,
+    font_size: f32,
+) -> Option<String> {
+    let nested = tree.get_nested_by_name(zoomed);
+    let y = map_rect.y - font_size * 2.0;
+    let mut x = map_rect.x;
+    let mut clicked = None;
+    let mut parent_name: Option<&str> = None;
+    for node in &nested {
+        if parent_name.is_some() {
+            draw_text(BREADCRUMB_SEPARATOR, x, y + font_size, font_size, GRAY);
+            x += measure_text(BREADCRUMB_SEPARATOR, None, font_size as u16, 1.0).width;
+        }
+        let text = short_name(&node.name, parent_name);
+        let width = measure_text(&text, None, font_size as u16, 1.0).width;
+        let rect = Rect::new(x, y, width, font_size * 1.5);
+        let is_zoomed = node.name == zoomed;
+        if !is_zoomed && rect.contains(Vec2::from(mouse_position())) {
+            draw_rect(rect, GRAY);
+        }
+        if !is_zoomed && is_rect_clicked(&rect, MouseButton::Left) {
+            clicked = Some(node.name.clone());
+        }
+        draw_text(&text, x, y + font_size, font_size, BLACK);
+        x += width;
+        parent_name = Some(&node.name);
+    }
+    let hint = "  (Backspace to zoom out)";
+    draw_text(hint, x, y + font_size, font_size, GRAY);
+    clicked
+}
+
+/// The name of a node without the name of its parent, e.g. "ui.rs" instead of "./src/ui.rs".
+fn short_name(name: &str, parent_name: Option<&str>) -> String {
+    parent_name
+        .and_then(|parent| name.strip_prefix(parent))
+        .map(|short| short.trim_start_matches('/'))
+        .filter(|short| !short.is_empty())
+        .unwrap_or(name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_name() {
+        assert_eq!(short_name(".", None), ".");
+        assert_eq!(short_name("./src", Some(".")), "src");
+        assert_eq!(short_name("./src/ui.rs", Some("./src")), "ui.rs");
+        assert_eq!(short_name("other", Some("./src")), "other");
+    }
+}